@@ -0,0 +1,7 @@
+// Frontend agnostic core of the duels: the registry of the games, the turns of a match and the
+// wasm sandbox running the programs
+pub mod registry;
+pub mod runner;
+pub mod sandbox;
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,124 @@
+use anyhow::{Error, Result};
+use duel_game::{ConfigOption, DuelGame, GameConfig, GameOption, GameOutcome, PlayerTurn, Score};
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+// Object safe version of DuelGame, inputs are given as the raw program output
+pub trait AnyGame: Send + Sync {
+    fn to_console(&self, n: PlayerTurn) -> String;
+    fn to_discord(&self) -> String;
+    fn play(&mut self, output: &str, n: PlayerTurn) -> Result<GameOutcome>;
+    // Parsed program output, None if it cannot be parsed
+    fn input_json(&self, output: &str) -> Option<serde_json::Value>;
+    fn score(&self) -> Option<Score>;
+    fn save(&self) -> Result<serde_json::Value>;
+}
+
+impl<GAME: DuelGame> AnyGame for GAME {
+    fn to_console(&self, n: PlayerTurn) -> String {
+        match n {
+            PlayerTurn::Player1 => self.to_console_player1(),
+            PlayerTurn::Player2 => self.to_console_player2(),
+        }
+    }
+
+    fn to_discord(&self) -> String {
+        DuelGame::to_discord(self)
+    }
+
+    fn play(&mut self, output: &str, n: PlayerTurn) -> Result<GameOutcome> {
+        let input = match GAME::Input::from_str(output) {
+            Ok(game_input) => game_input,
+            Err(_) => return Err(Error::msg(format!("Error parsing game input: {}", output))),
+        };
+        match DuelGame::play(self, input, n) {
+            Ok(outcome) => Ok(outcome),
+            Err(why) => Err(Error::msg(format!("Error playing: {}", why))),
+        }
+    }
+
+    fn input_json(&self, output: &str) -> Option<serde_json::Value> {
+        let input = GAME::Input::from_str(output).ok()?;
+        serde_json::to_value(input).ok()
+    }
+
+    fn score(&self) -> Option<Score> {
+        DuelGame::score(self)
+    }
+
+    fn save(&self) -> Result<serde_json::Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+pub trait GameEntry: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn description(&self) -> &'static str;
+    fn options(&self) -> Vec<ConfigOption>;
+    // Return the game and its displayed config
+    fn new_game(&self, options: &[GameOption]) -> Result<(Box<dyn AnyGame>, String)>;
+    fn load_game(&self, value: serde_json::Value) -> Result<Box<dyn AnyGame>>;
+}
+
+struct Entry<GAME> {
+    name: &'static str,
+    description: &'static str,
+    game: PhantomData<fn() -> GAME>,
+}
+
+impl<GAME: DuelGame + 'static> GameEntry for Entry<GAME> {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn options(&self) -> Vec<ConfigOption> {
+        GAME::Config::options()
+    }
+
+    fn new_game(&self, options: &[GameOption]) -> Result<(Box<dyn AnyGame>, String)> {
+        let config = GAME::Config::from_options(options)?;
+        let displayed_config = format!("{}", config);
+        Ok((Box::new(GAME::new(config)), displayed_config))
+    }
+
+    fn load_game(&self, value: serde_json::Value) -> Result<Box<dyn AnyGame>> {
+        let game: GAME = serde_json::from_value(value)?;
+        Ok(Box::new(game))
+    }
+}
+
+#[derive(Default)]
+pub struct GameRegistry {
+    entries: Vec<Box<dyn GameEntry>>,
+}
+
+impl GameRegistry {
+    pub fn register<GAME: DuelGame + 'static>(
+        mut self,
+        name: &'static str,
+        description: &'static str,
+    ) -> Self {
+        assert!(self.get(name).is_none(), "Game {} registered twice", name);
+        self.entries.push(Box::new(Entry::<GAME> {
+            name,
+            description,
+            game: PhantomData,
+        }));
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn GameEntry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.name() == name)
+            .map(|entry| entry.as_ref())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn GameEntry> {
+        self.entries.iter().map(|entry| entry.as_ref())
+    }
+}
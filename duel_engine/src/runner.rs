@@ -0,0 +1,73 @@
+use crate::registry::AnyGame;
+use crate::sandbox::Sandbox;
+use anyhow::Result;
+use duel_game::{GameOutcome, PlayerTurn, Score};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Instant;
+
+// One turn of a program, as logged in the replays
+#[derive(Serialize, Deserialize)]
+pub struct ReplayTurn {
+    pub player_turn: PlayerTurn,
+    pub stdin: String,
+    pub stdout: Option<String>,
+    pub input: Option<serde_json::Value>,
+    pub duration_ms: u64,
+    // Fuel consumed by the program, None if it did not finish
+    #[serde(default)]
+    pub fuel: Option<u64>,
+    // Board after the turn, None if the turn failed
+    pub board: Option<String>,
+    pub error: Option<String>,
+}
+
+// Run the program of the player on the game and play its output, the turn is returned even
+// when it failed
+pub async fn play_turn(
+    game: &mut dyn AnyGame,
+    player_turn: PlayerTurn,
+    file_path: &Path,
+    sandbox: &Sandbox,
+) -> (ReplayTurn, Result<GameOutcome>) {
+    let stdin = game.to_console(player_turn);
+
+    let start = Instant::now();
+    let output = sandbox.run_file(stdin.clone(), file_path).await;
+    let mut turn = ReplayTurn {
+        player_turn,
+        stdin,
+        stdout: None,
+        input: None,
+        duration_ms: start.elapsed().as_millis() as u64,
+        fuel: None,
+        board: None,
+        error: None,
+    };
+
+    let result = output.and_then(|(stdout, fuel)| {
+        turn.fuel = Some(fuel);
+        turn.input = game.input_json(stdout.as_str());
+        let result = game.play(stdout.as_str(), player_turn);
+        turn.stdout = Some(stdout);
+        result
+    });
+    match &result {
+        Ok(_) => turn.board = Some(game.to_discord()),
+        Err(why) => turn.error = Some(why.to_string()),
+    }
+    (turn, result)
+}
+
+pub fn outcome_message(outcome: GameOutcome, score: Option<Score>) -> String {
+    let result = match outcome {
+        GameOutcome::Ongoing => "Game ongoing".to_string(),
+        GameOutcome::Win(PlayerTurn::Player1) => "Program 1 WIN".to_string(),
+        GameOutcome::Win(PlayerTurn::Player2) => "Program 2 WIN".to_string(),
+        GameOutcome::Draw => "DRAW".to_string(),
+    };
+    match score {
+        Some(score) => format!("{} ({} - {})", result, score.player1, score.player2),
+        None => result,
+    }
+}
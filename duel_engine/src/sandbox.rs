@@ -0,0 +1,392 @@
+use anyhow::{Error, Result};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use wasi_common::pipe::{ReadPipe, WritePipe};
+use wasi_common::sched::{Poll, WasiSched};
+use wasi_common::Table;
+use wasmtime::{Config, Engine, Linker, Module, ResourceLimiter, Store, Trap};
+use wasmtime_wasi::{clocks_ctx, random_ctx, sched_ctx, WasiCtx};
+
+// Resources a program can use for one move
+#[derive(Debug, Copy, Clone)]
+pub struct Limits {
+    // Roughly one unit per executed wasm instruction
+    pub fuel: u64,
+    // Bytes of linear memory
+    pub memory: usize,
+    pub table_elements: u32,
+    // Also the maximum number of memories and of tables
+    pub instances: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            fuel: 1_000_000_000,
+            memory: 64 << 20,
+            table_elements: 10_000,
+            instances: 10,
+        }
+    }
+}
+
+impl Limits {
+    const FUEL_VAR: &'static str = "DUEL_FUEL";
+    const MEMORY_MB_VAR: &'static str = "DUEL_MEMORY_MB";
+    const TABLE_ELEMENTS_VAR: &'static str = "DUEL_TABLE_ELEMENTS";
+    const INSTANCES_VAR: &'static str = "DUEL_INSTANCES";
+
+    // Default limits, overridden by the environment variables that are set
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        Self {
+            fuel: env_or(Limits::FUEL_VAR, default.fuel),
+            memory: env_or(Limits::MEMORY_MB_VAR, default.memory >> 20) << 20,
+            table_elements: env_or(Limits::TABLE_ELEMENTS_VAR, default.table_elements),
+            instances: env_or(Limits::INSTANCES_VAR, default.instances),
+        }
+    }
+}
+
+fn env_or<T: FromStr>(name: &str, default: T) -> T {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(default)
+}
+
+// Wall clock time a program has for one move
+const MOVE_TIMEOUT: Duration = Duration::from_secs(3);
+// How often the engine epoch advances, the precision of the move timeout
+const EPOCH_TICK: Duration = Duration::from_millis(10);
+
+#[derive(Debug)]
+pub enum ProgramTimeout {
+    Duration(Duration),
+    // The program was stopped after using all its fuel
+    Fuel(u64),
+}
+
+impl std::error::Error for ProgramTimeout {}
+
+impl Display for ProgramTimeout {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProgramTimeout::Duration(duration) => {
+                write!(f, "Program timed out (>{}s)", duration.as_secs())
+            }
+            ProgramTimeout::Fuel(fuel) => write!(f, "Program ran out of fuel (>{})", fuel),
+        }
+    }
+}
+
+// The program asked for more than its limits, it is stopped instead of getting less
+#[derive(Debug, Copy, Clone)]
+pub enum LimitExceeded {
+    Memory(usize),
+    TableElements(u32),
+}
+
+impl std::error::Error for LimitExceeded {}
+
+impl Display for LimitExceeded {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LimitExceeded::Memory(memory) => {
+                write!(f, "exceeded memory limit ({} MiB)", memory >> 20)
+            }
+            LimitExceeded::TableElements(elements) => {
+                write!(f, "exceeded table limit ({} elements)", elements)
+            }
+        }
+    }
+}
+
+struct ProgramLimiter {
+    limits: Limits,
+    exceeded: Option<LimitExceeded>,
+}
+
+impl ResourceLimiter for ProgramLimiter {
+    fn memory_growing(
+        &mut self,
+        _current: usize,
+        desired: usize,
+        _maximum: Option<usize>,
+    ) -> Result<bool> {
+        if desired > self.limits.memory {
+            let exceeded = LimitExceeded::Memory(self.limits.memory);
+            self.exceeded = Some(exceeded);
+            return Err(exceeded.into());
+        }
+        Ok(true)
+    }
+
+    fn table_growing(
+        &mut self,
+        _current: u32,
+        desired: u32,
+        _maximum: Option<u32>,
+    ) -> Result<bool> {
+        if desired > self.limits.table_elements {
+            let exceeded = LimitExceeded::TableElements(self.limits.table_elements);
+            self.exceeded = Some(exceeded);
+            return Err(exceeded.into());
+        }
+        Ok(true)
+    }
+
+    fn instances(&self) -> usize {
+        self.limits.instances
+    }
+
+    fn tables(&self) -> usize {
+        self.limits.instances
+    }
+
+    fn memories(&self) -> usize {
+        self.limits.instances
+    }
+}
+
+// Sleeps and polls of a program end at the move deadline: the epoch only interrupts running wasm
+// code, a program blocked in the host would keep its thread
+struct DeadlineSched {
+    inner: Box<dyn WasiSched>,
+    deadline: Instant,
+}
+
+impl DeadlineSched {
+    // Waits until the deadline instead of the whole duration, then stops the program
+    async fn wait(&self, duration: Duration) -> Result<(), wasi_common::Error> {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        if duration <= remaining {
+            return self.inner.sleep(duration).await;
+        }
+        self.inner.sleep(remaining).await?;
+        Err(wasi_common::Error::trap(
+            ProgramTimeout::Duration(MOVE_TIMEOUT).into(),
+        ))
+    }
+}
+
+#[async_trait::async_trait]
+impl WasiSched for DeadlineSched {
+    async fn poll_oneoff<'a>(&self, poll: &mut Poll<'a>) -> Result<(), wasi_common::Error> {
+        // Only a poll on clocks alone can block, the pipes of a program are not pollable
+        if poll.rw_subscriptions().next().is_none() {
+            if let Some(duration) = poll
+                .earliest_clock_deadline()
+                .and_then(|clock| clock.duration_until())
+            {
+                return self.wait(duration).await;
+            }
+        }
+        self.inner.poll_oneoff(poll).await
+    }
+
+    async fn sched_yield(&self) -> Result<(), wasi_common::Error> {
+        self.inner.sched_yield().await
+    }
+
+    async fn sleep(&self, duration: Duration) -> Result<(), wasi_common::Error> {
+        self.wait(duration).await
+    }
+}
+
+struct ProgramState {
+    wasi: WasiCtx,
+    limiter: ProgramLimiter,
+}
+
+// Runs the programs on the blocking thread pool: wasm execution never yields, it would stall
+// every other task of an async worker. A program stops by itself at the move deadline
+pub struct Sandbox {
+    pub limits: Limits,
+    // One permit per program running at the same time
+    permits: Arc<Semaphore>,
+    programs: Arc<Programs>,
+}
+
+impl Sandbox {
+    const CONCURRENCY_VAR: &'static str = "DUEL_CONCURRENCY";
+
+    pub fn new(limits: Limits, concurrency: usize) -> Result<Self> {
+        Ok(Self {
+            limits,
+            permits: Arc::new(Semaphore::new(concurrency.max(1))),
+            programs: Arc::new(Programs::new()?),
+        })
+    }
+
+    // Limits and concurrency from the environment, one program per CPU by default
+    pub fn from_env() -> Result<Self> {
+        let concurrency = std::thread::available_parallelism().map_or(1, |cpus| cpus.get());
+        Sandbox::new(
+            Limits::from_env(),
+            env_or(Sandbox::CONCURRENCY_VAR, concurrency),
+        )
+    }
+
+    // Output of the program and the fuel it consumed
+    pub async fn run_file(&self, console_str: String, file_path: &Path) -> Result<(String, u64)> {
+        let permit = self.permits.clone().acquire_owned().await?;
+        let programs = self.programs.clone();
+        let file_path = file_path.to_path_buf();
+        let limits = self.limits;
+        tokio::task::spawn_blocking(move || {
+            // The permit is released once the program stopped
+            let _permit = permit;
+            programs.run(console_str, &file_path, limits)
+        })
+        .await?
+    }
+}
+
+// Module compiled from a program file, along with the SHA-256 of the content it was compiled from
+struct CachedModule {
+    digest: [u8; 32],
+    module: Module,
+}
+
+// Compiled programs shared by every run, each run only gets its own store
+struct Programs {
+    engine: Engine,
+    linker: Linker<ProgramState>,
+    modules: Mutex<HashMap<PathBuf, CachedModule>>,
+}
+
+impl Programs {
+    fn new() -> Result<Self> {
+        // Fuel stops a runaway program even though it never yields to the runtime, the epoch
+        // stops it once its move takes too long
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        config.epoch_interruption(true);
+        let engine = Engine::new(&config)?;
+
+        // The engine lives as long as the bot, so does the thread advancing its epoch
+        let ticker = engine.clone();
+        std::thread::Builder::new()
+            .name("epoch-ticker".to_string())
+            .spawn(move || loop {
+                std::thread::sleep(EPOCH_TICK);
+                ticker.increment_epoch();
+            })?;
+        let mut linker = Linker::new(&engine);
+        wasmtime_wasi::add_to_linker(&mut linker, |state: &mut ProgramState| &mut state.wasi)?;
+        Ok(Self {
+            engine,
+            linker,
+            modules: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn modules(&self) -> MutexGuard<'_, HashMap<PathBuf, CachedModule>> {
+        // A panic while holding the lock cannot leave a half inserted module
+        self.modules
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // Compiled only when the file is new or its content changed, a program whose file can no
+    // longer be read leaves the cache
+    fn module(&self, file_path: &Path) -> Result<Module> {
+        let bytes = match std::fs::read(file_path) {
+            Ok(bytes) => bytes,
+            Err(why) => {
+                self.modules().remove(file_path);
+                return Err(why.into());
+            }
+        };
+        let digest: [u8; 32] = Sha256::digest(&bytes).into();
+        if let Some(cached) = self.modules().get(file_path) {
+            if cached.digest == digest {
+                return Ok(cached.module.clone());
+            }
+        }
+
+        let module = Module::new(&self.engine, bytes)?;
+        self.modules().insert(
+            file_path.to_path_buf(),
+            CachedModule {
+                digest,
+                module: module.clone(),
+            },
+        );
+        Ok(module)
+    }
+
+    fn run(
+        &self,
+        grid_console_string: String,
+        file_path: &Path,
+        limits: Limits,
+    ) -> Result<(String, u64)> {
+        let module = self.module(file_path)?;
+
+        let stdin = ReadPipe::from(grid_console_string);
+        let stdout = WritePipe::new_in_memory();
+
+        let sched = DeadlineSched {
+            inner: sched_ctx(),
+            deadline: Instant::now() + MOVE_TIMEOUT,
+        };
+        let wasi = WasiCtx::new(random_ctx(), clocks_ctx(), Box::new(sched), Table::new());
+        wasi.set_stdin(Box::new(stdin));
+        wasi.set_stdout(Box::new(stdout.clone()));
+
+        let state = ProgramState {
+            wasi,
+            limiter: ProgramLimiter {
+                limits,
+                exceeded: None,
+            },
+        };
+        let mut store = Store::new(&self.engine, state);
+        store.limiter(|state| &mut state.limiter);
+        store.add_fuel(limits.fuel)?;
+        store.set_epoch_deadline((MOVE_TIMEOUT.as_millis() / EPOCH_TICK.as_millis()) as u64);
+
+        // The limits can be exceeded while instantiating as well as while running
+        let result = self
+            .linker
+            .instantiate(&mut store, &module)
+            .and_then(|instance| {
+                instance
+                    .get_typed_func::<(), ()>(&mut store, "_start")?
+                    .call(&mut store, ())
+            });
+        if let Err(why) = result {
+            if let Some(exceeded) = store.data().limiter.exceeded {
+                return Err(exceeded.into());
+            }
+            // Raised by the scheduler, under the wasm backtrace
+            if why.root_cause().is::<ProgramTimeout>() {
+                return Err(ProgramTimeout::Duration(MOVE_TIMEOUT).into());
+            }
+            return match why.downcast_ref::<Trap>() {
+                Some(Trap::OutOfFuel) => Err(ProgramTimeout::Fuel(limits.fuel).into()),
+                Some(Trap::Interrupt) => Err(ProgramTimeout::Duration(MOVE_TIMEOUT).into()),
+                _ => Err(why),
+            };
+        }
+
+        let fuel = store.fuel_consumed().unwrap_or_default();
+        drop(store);
+        match stdout.try_into_inner() {
+            Ok(res) => {
+                let bytes_res = res.into_inner();
+                let str_res = String::from_utf8_lossy(bytes_res.as_slice()).to_string();
+                Ok((str_res, fuel))
+            }
+            Err(_) => Err(Error::msg("Error getting stdout result")),
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use crate::sandbox::{LimitExceeded, Limits, ProgramTimeout, Sandbox};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+// Echo stdin back to stdout
+const ECHO: &str = r#"(module
+  (import "wasi_snapshot_preview1" "fd_read" (func $fd_read (param i32 i32 i32 i32) (result i32)))
+  (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
+  (memory (export "memory") 1)
+  (func (export "_start")
+    (i32.store (i32.const 0) (i32.const 16))
+    (i32.store (i32.const 4) (i32.const 64))
+    (drop (call $fd_read (i32.const 0) (i32.const 0) (i32.const 1) (i32.const 8)))
+    (i32.store (i32.const 4) (i32.load (i32.const 8)))
+    (drop (call $fd_write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 8)))))"#;
+
+const LOOP: &str = r#"(module
+  (memory (export "memory") 1)
+  (func (export "_start") (loop $l (br $l))))"#;
+
+// A single relative clock subscription of 100s, the way wasi-libc sleeps
+const SLEEP: &str = r#"(module
+  (import "wasi_snapshot_preview1" "poll_oneoff" (func $poll (param i32 i32 i32 i32) (result i32)))
+  (memory (export "memory") 1)
+  (func (export "_start")
+    (i32.store8 (i32.const 8) (i32.const 0))
+    (i32.store (i32.const 16) (i32.const 1))
+    (i64.store (i32.const 24) (i64.const 100000000000))
+    (drop (call $poll (i32.const 0) (i32.const 64) (i32.const 1) (i32.const 128)))))"#;
+
+const GROW_MEMORY: &str = r#"(module
+  (memory (export "memory") 1)
+  (func (export "_start") (drop (memory.grow (i32.const 16)))))"#;
+
+const GROW_TABLE: &str = r#"(module
+  (memory (export "memory") 1)
+  (table 1 funcref)
+  (func (export "_start") (drop (table.grow (ref.null func) (i32.const 100)))))"#;
+
+// Write the text of a program to a file of its own, wasm programs can be given as text
+fn program(name: &str, wat: &str) -> PathBuf {
+    let file_path = std::env::temp_dir().join(format!("duel_engine_{}.wat", name));
+    std::fs::write(&file_path, wat).unwrap();
+    file_path
+}
+
+fn sandbox(limits: Limits) -> Sandbox {
+    Sandbox::new(limits, 1).unwrap()
+}
+
+#[tokio::test]
+async fn output_and_fuel() {
+    let sandbox = sandbox(Limits::default());
+    let file_path = program("echo", ECHO);
+    let (output, fuel) = sandbox
+        .run_file("1 2\n".to_string(), &file_path)
+        .await
+        .unwrap();
+    assert_eq!(output, "1 2\n");
+    assert!(fuel > 0);
+}
+
+#[tokio::test]
+async fn out_of_fuel() {
+    let limits = Limits {
+        fuel: 1000,
+        ..Limits::default()
+    };
+    let file_path = program("out_of_fuel", LOOP);
+    let why = sandbox(limits)
+        .run_file(String::new(), &file_path)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        why.downcast_ref::<ProgramTimeout>(),
+        Some(ProgramTimeout::Fuel(1000))
+    ));
+}
+
+#[tokio::test]
+async fn endless_loop_times_out() {
+    let limits = Limits {
+        fuel: u64::MAX,
+        ..Limits::default()
+    };
+    let file_path = program("endless_loop", LOOP);
+    let why = sandbox(limits)
+        .run_file(String::new(), &file_path)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        why.downcast_ref::<ProgramTimeout>(),
+        Some(ProgramTimeout::Duration(_))
+    ));
+}
+
+#[tokio::test]
+async fn sleep_is_cut_at_the_deadline() {
+    let sandbox = sandbox(Limits::default());
+    let start = Instant::now();
+    let why = sandbox
+        .run_file(String::new(), &program("sleep", SLEEP))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        why.downcast_ref::<ProgramTimeout>(),
+        Some(ProgramTimeout::Duration(_))
+    ));
+    assert!(start.elapsed() < Duration::from_secs(10));
+
+    // The only permit is free again
+    let (output, _) = sandbox
+        .run_file("ok".to_string(), &program("echo_after_sleep", ECHO))
+        .await
+        .unwrap();
+    assert_eq!(output, "ok");
+}
+
+#[tokio::test]
+async fn memory_limit() {
+    let limits = Limits {
+        memory: 1 << 20,
+        ..Limits::default()
+    };
+    let file_path = program("grow_memory", GROW_MEMORY);
+    let why = sandbox(limits)
+        .run_file(String::new(), &file_path)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        why.downcast_ref::<LimitExceeded>(),
+        Some(LimitExceeded::Memory(1048576))
+    ));
+}
+
+#[tokio::test]
+async fn table_limit() {
+    let limits = Limits {
+        table_elements: 10,
+        ..Limits::default()
+    };
+    let file_path = program("grow_table", GROW_TABLE);
+    let why = sandbox(limits)
+        .run_file(String::new(), &file_path)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        why.downcast_ref::<LimitExceeded>(),
+        Some(LimitExceeded::TableElements(10))
+    ));
+}
+
+#[tokio::test]
+async fn instance_limit() {
+    let limits = Limits {
+        instances: 0,
+        ..Limits::default()
+    };
+    let file_path = program("no_instance", ECHO);
+    assert!(sandbox(limits)
+        .run_file(String::new(), &file_path)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn cached_module_follows_the_file_content() {
+    let sandbox = sandbox(Limits::default());
+    let file_path = program("cached", ECHO);
+    for _ in 0..2 {
+        let (output, _) = sandbox.run_file("a".to_string(), &file_path).await.unwrap();
+        assert_eq!(output, "a");
+    }
+
+    // Same size, another program writing nothing
+    let silent = r#"(module (memory (export "memory") 1) (func (export "_start")))"#;
+    std::fs::write(&file_path, format!("{:<1$}", silent, ECHO.len())).unwrap();
+    let (output, _) = sandbox.run_file("a".to_string(), &file_path).await.unwrap();
+    assert_eq!(output, "");
+
+    std::fs::remove_file(&file_path).unwrap();
+    assert!(sandbox.run_file("a".to_string(), &file_path).await.is_err());
+}
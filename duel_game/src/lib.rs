@@ -1,10 +1,10 @@
-use serenity::builder::CreateApplicationCommand;
-use serenity::model::prelude::application_command::CommandDataOption;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use std::error::Error;
 use std::fmt::Display;
 use std::str::FromStr;
 
-#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
 pub enum PlayerTurn {
     Player1,
     Player2,
@@ -19,9 +19,23 @@ impl PlayerTurn {
     }
 }
 
-pub trait DiscordDuelGame: Send + Sync {
-    type Config: DiscordConfig + Send + Sync + Display;
-    type Input: FromStr + Send + Sync;
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum GameOutcome {
+    Ongoing,
+    Win(PlayerTurn),
+    Draw,
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Score {
+    pub player1: usize,
+    pub player2: usize,
+}
+
+// Games, configs and inputs are serialized to be saved, replayed or exported
+pub trait DuelGame: Send + Sync + Serialize + DeserializeOwned {
+    type Config: GameConfig + Send + Sync + Display + Serialize + DeserializeOwned;
+    type Input: FromStr + Send + Sync + Serialize + DeserializeOwned;
     type GameError: Error;
 
     fn new(config: Self::Config) -> Self;
@@ -31,10 +45,90 @@ pub trait DiscordDuelGame: Send + Sync {
 
     fn to_discord(&self) -> String;
 
-    fn play(&mut self, player_input: Self::Input, n: PlayerTurn) -> Result<bool, Self::GameError>;
+    fn play(
+        &mut self,
+        player_input: Self::Input,
+        n: PlayerTurn,
+    ) -> Result<GameOutcome, Self::GameError>;
+
+    // Games without a score (like Amazons) only have a winner
+    fn score(&self) -> Option<Score> {
+        None
+    }
+}
+
+// Value of a config option, whatever the frontend it comes from
+#[derive(Debug, PartialEq, Clone)]
+pub enum OptionValue {
+    String(String),
+    Integer(i64),
+    Boolean(bool),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct GameOption {
+    pub name: String,
+    pub value: OptionValue,
+}
+
+// Name of the option seeding the random board of a game, a series shifts it for every game
+pub const SEED_OPTION: &str = "seed";
+
+// "name=value", the value is an integer or a boolean when it can be parsed as one
+impl FromStr for GameOption {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, value) = s
+            .split_once('=')
+            .ok_or(format!("Expected name=value: {}", s))?;
+        let value = value.trim();
+        let value = if let Ok(i) = value.parse() {
+            OptionValue::Integer(i)
+        } else if let Ok(b) = value.parse() {
+            OptionValue::Boolean(b)
+        } else {
+            OptionValue::String(value.to_string())
+        };
+        Ok(Self {
+            name: name.trim().to_string(),
+            value,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum OptionKind {
+    String,
+    Integer,
+    Boolean,
+}
+
+// Description of a config option, every frontend shows it its own way (Discord subcommand
+// option, command line argument...)
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ConfigOption {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub kind: OptionKind,
+    // Allowed values of a string option, any value if empty
+    pub choices: Vec<&'static str>,
+}
+
+impl ConfigOption {
+    pub fn new(name: &'static str, description: &'static str, kind: OptionKind) -> Self {
+        Self {
+            name,
+            description,
+            kind,
+            choices: Vec::new(),
+        }
+    }
 }
 
-pub trait DiscordConfig {
-    fn create_command(option: &mut CreateApplicationCommand) -> &mut CreateApplicationCommand;
-    fn from_options(options: &[CommandDataOption]) -> Self;
+pub trait GameConfig: Sized {
+    // Options are all optional, a missing option takes its default value
+    fn options() -> Vec<ConfigOption>;
+    // Invalid options are clamped when possible, rejected otherwise
+    fn from_options(options: &[GameOption]) -> anyhow::Result<Self>;
 }
@@ -1,86 +1,181 @@
-use duel_game::DiscordConfig;
-use serenity::builder::CreateApplicationCommand;
-use serenity::model::application::command::CommandOptionType;
-use serenity::model::prelude::application_command::{CommandDataOption, CommandDataOptionValue};
+use crate::placement::Placement;
+use crate::AmazonsGame;
+use anyhow::Error;
+use duel_game::{
+    ConfigOption, GameConfig, GameOption, OptionKind, OptionValue, PlayerTurn, SEED_OPTION,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub struct AmazonsConfig {
     pub width: usize,
     pub height: usize,
     pub queens: usize,
+    pub placement: Placement,
+    // Seed of the initial queens placement, the same seed gives the same board
+    pub seed: u64,
+    // Custom starting position (see AmazonsGame::from_board), replaces the placement
+    pub board: Option<String>,
 }
 
 impl AmazonsConfig {
     const WIDTH_NAME: &'static str = "width";
     const HEIGHT_NAME: &'static str = "height";
     const QUEENS_NAME: &'static str = "queens";
+    const PLACEMENT_NAME: &'static str = "placement";
+    const SEED_NAME: &'static str = SEED_OPTION;
+    const BOARD_NAME: &'static str = "board";
+
+    pub const MIN_SIZE: usize = 5;
+    pub const MAX_SIZE: usize = 15;
+    pub const MIN_QUEENS: usize = 2;
+    pub const MAX_QUEENS: usize = 6;
+    // Discord integers are limited to 2^53, so a generated seed can be typed back
+    pub const MAX_SEED: u64 = (1 << 53) - 1;
 }
 
-impl DiscordConfig for AmazonsConfig {
-    fn create_command(command: &mut CreateApplicationCommand) -> &mut CreateApplicationCommand {
-        command
-            .create_option(|option| {
-                option
-                    .name(AmazonsConfig::WIDTH_NAME)
-                    .description("Width of the grid (5-15)")
-                    .required(false)
-                    .kind(CommandOptionType::Integer)
-            })
-            .create_option(|option| {
-                option
-                    .name(AmazonsConfig::HEIGHT_NAME)
-                    .description("Height of the grid (5-15)")
-                    .required(false)
-                    .kind(CommandOptionType::Integer)
-            })
-            .create_option(|option| {
-                option
-                    .name(AmazonsConfig::QUEENS_NAME)
-                    .description("Number of queens (2-6)")
-                    .required(false)
-                    .kind(CommandOptionType::Integer)
-            })
+impl GameConfig for AmazonsConfig {
+    fn options() -> Vec<ConfigOption> {
+        vec![
+            ConfigOption::new(
+                AmazonsConfig::WIDTH_NAME,
+                "Width of the grid (5-15)",
+                OptionKind::Integer,
+            ),
+            ConfigOption::new(
+                AmazonsConfig::HEIGHT_NAME,
+                "Height of the grid (5-15)",
+                OptionKind::Integer,
+            ),
+            ConfigOption::new(
+                AmazonsConfig::QUEENS_NAME,
+                "Number of queens (2-6)",
+                OptionKind::Integer,
+            ),
+            ConfigOption {
+                choices: Placement::ALL
+                    .iter()
+                    .map(|placement| placement.name())
+                    .collect(),
+                ..ConfigOption::new(
+                    AmazonsConfig::PLACEMENT_NAME,
+                    "Placement of the queens (classic is 10x10 with 4 queens)",
+                    OptionKind::String,
+                )
+            },
+            ConfigOption::new(
+                AmazonsConfig::SEED_NAME,
+                "Seed of the board (random if not given)",
+                OptionKind::Integer,
+            ),
+            ConfigOption::new(
+                AmazonsConfig::BOARD_NAME,
+                "Starting board with rows separated by '/' (like *___+/_____/...)",
+                OptionKind::String,
+            ),
+        ]
     }
 
-    fn from_options(options: &[CommandDataOption]) -> Self {
+    fn from_options(options: &[GameOption]) -> anyhow::Result<Self> {
         let mut width = 8;
         let mut height = 8;
         let mut queens = 2;
+        let mut placement = Placement::Random;
+        let mut seed = rand::thread_rng().gen_range(0..=AmazonsConfig::MAX_SEED);
+        let mut board = None;
         for option in options.iter() {
             match option.name.as_str() {
                 AmazonsConfig::WIDTH_NAME => {
-                    if let Some(CommandDataOptionValue::Integer(width_desired)) = option.resolved {
-                        width = width_desired.clamp(5, 15) as usize;
+                    if let OptionValue::Integer(width_desired) = option.value {
+                        width = width_desired.clamp(
+                            AmazonsConfig::MIN_SIZE as i64,
+                            AmazonsConfig::MAX_SIZE as i64,
+                        ) as usize;
                     }
                 }
                 AmazonsConfig::HEIGHT_NAME => {
-                    if let Some(CommandDataOptionValue::Integer(height_desired)) = option.resolved {
-                        height = height_desired.clamp(5, 15) as usize;
+                    if let OptionValue::Integer(height_desired) = option.value {
+                        height = height_desired.clamp(
+                            AmazonsConfig::MIN_SIZE as i64,
+                            AmazonsConfig::MAX_SIZE as i64,
+                        ) as usize;
                     }
                 }
                 AmazonsConfig::QUEENS_NAME => {
-                    if let Some(CommandDataOptionValue::Integer(queens_desired)) = option.resolved {
-                        queens = queens_desired.clamp(2, 6) as usize
+                    if let OptionValue::Integer(queens_desired) = option.value {
+                        queens = queens_desired.clamp(
+                            AmazonsConfig::MIN_QUEENS as i64,
+                            AmazonsConfig::MAX_QUEENS as i64,
+                        ) as usize
+                    }
+                }
+                AmazonsConfig::PLACEMENT_NAME => {
+                    if let OptionValue::String(placement_desired) = &option.value {
+                        placement = placement_desired.parse().unwrap_or(placement);
+                    }
+                }
+                AmazonsConfig::SEED_NAME => {
+                    if let OptionValue::Integer(seed_desired) = option.value {
+                        // Refused rather than changed, the seed has to give the board it names
+                        seed = u64::try_from(seed_desired)
+                            .ok()
+                            .filter(|seed| *seed <= AmazonsConfig::MAX_SEED)
+                            .ok_or(Error::msg(format!(
+                                "Seed should be between 0 and {}",
+                                AmazonsConfig::MAX_SEED
+                            )))?;
+                    }
+                }
+                AmazonsConfig::BOARD_NAME => {
+                    if let OptionValue::String(board_desired) = &option.value {
+                        board = Some(board_desired.clone());
                     }
                 }
                 _ => (),
             }
         }
 
-        Self {
+        if placement == Placement::Classic {
+            width = Placement::CLASSIC_SIZE;
+            height = Placement::CLASSIC_SIZE;
+            queens = Placement::CLASSIC_QUEENS;
+        }
+
+        // The board gives its own size and queens, it is stored in its normalized form
+        if let Some(board_desired) = board {
+            let game = AmazonsGame::from_board(&board_desired)?;
+            width = game.0.len();
+            height = game.0[0].len();
+            queens = game.queens(PlayerTurn::Player1).len();
+            board = Some(game.to_board());
+        }
+
+        Ok(Self {
             width,
             height,
             queens,
-        }
+            placement,
+            seed,
+            board,
+        })
     }
 }
 
 impl Display for AmazonsConfig {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "Width: {} | Height: {} | Queens: {}",
-            self.width, self.height, self.queens
-        )
+        match &self.board {
+            Some(board) => write!(
+                f,
+                "Width: {} | Height: {} | Queens: {} | Board: {}",
+                self.width, self.height, self.queens, board
+            ),
+            None => write!(
+                f,
+                "Width: {} | Height: {} | Queens: {} | Placement: {} | Seed: {}",
+                self.width, self.height, self.queens, self.placement, self.seed
+            ),
+        }
     }
 }
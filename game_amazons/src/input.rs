@@ -1,13 +1,23 @@
 use crate::pos::Pos;
 use anyhow::Error;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
 pub struct PlayerInput {
     pub from: Pos,
     pub to: Pos,
     pub arrow: Pos,
 }
 
+// Same notation as the program output: "(x,y)|(x,y)|(x,y)"
+impl Display for PlayerInput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}|{}|{}", self.from, self.to, self.arrow)
+    }
+}
+
 impl FromStr for PlayerInput {
     type Err = Error;
 
@@ -2,16 +2,22 @@
 
 pub mod config;
 pub mod input;
+pub mod placement;
 pub mod pos;
+pub mod record;
+#[cfg(test)]
+mod tests;
 
 use crate::config::AmazonsConfig;
 use crate::input::PlayerInput;
-use duel_game::{DiscordDuelGame, PlayerTurn};
+use duel_game::{DuelGame, GameOutcome, PlayerTurn};
 use pos::Pos;
-use rand::prelude::IteratorRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
 pub enum GameCell {
     Empty,
     Amazon1,
@@ -40,38 +46,27 @@ impl Display for GameError {
     }
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct AmazonsGame(Vec<Vec<GameCell>>);
 
-impl DiscordDuelGame for AmazonsGame {
+impl DuelGame for AmazonsGame {
     type Config = AmazonsConfig;
     type Input = PlayerInput;
     type GameError = GameError;
 
     fn new(config: Self::Config) -> Self {
-        let mut rng = rand::thread_rng();
-
-        let queen1_xpos: Vec<usize> = (0..config.width).choose_multiple(&mut rng, config.queens);
-        let queen2_xpos: Vec<usize> = (0..config.width).choose_multiple(&mut rng, config.queens);
-        let queen1_ypos: Vec<usize> = (0..config.height).choose_multiple(&mut rng, config.queens);
-        let queen2_ypos: Vec<usize> = (0..config.height).choose_multiple(&mut rng, config.queens);
-
-        let mut grid: Vec<Vec<GameCell>> = (0..config.width)
-            .map(|_| {
-                (0..config.height)
-                    .map(|_| GameCell::Empty)
-                    .collect::<Vec<_>>()
-            })
-            .collect();
-
-        for (x, y) in queen1_xpos.into_iter().zip(queen1_ypos) {
-            grid[x][y] = GameCell::Amazon1;
+        if let Some(board) = &config.board {
+            return Self::from_board(board).expect("Board is validated by from_options");
         }
 
-        for (x, y) in queen2_xpos.into_iter().zip(queen2_ypos) {
-            grid[x][y] = GameCell::Amazon2;
-        }
-
-        Self(grid)
+        // StdRng may change with rand versions, a shared seed has to give the same board
+        let mut rng = ChaCha8Rng::seed_from_u64(config.seed);
+        let (queens1, queens2) =
+            config
+                .placement
+                .queens(config.width, config.height, config.queens, &mut rng);
+        Self::from_queens(config.width, config.height, &queens1, &queens2)
+            .expect("Placements never overlap queens")
     }
 
     fn to_console_player1(&self) -> String {
@@ -131,61 +126,34 @@ impl DiscordDuelGame for AmazonsGame {
             .join("\n")
     }
 
-    // Return true if the game is ended, false otherwise
-    fn play(&mut self, player_input: Self::Input, n: PlayerTurn) -> Result<bool, Self::GameError> {
+    fn play(
+        &mut self,
+        player_input: Self::Input,
+        n: PlayerTurn,
+    ) -> Result<GameOutcome, Self::GameError> {
         let from_cell = self
             .get_cell(player_input.from)
             .ok_or(GameError::InputOutOfBounds)?;
 
-        match n {
-            PlayerTurn::Player1 => {
-                if *from_cell != GameCell::Amazon1 {
-                    return Err(GameError::InputInvalidPosition);
-                }
-            }
-            PlayerTurn::Player2 => {
-                if *from_cell != GameCell::Amazon2 {
-                    return Err(GameError::InputInvalidPosition);
-                }
-            }
+        if *from_cell != amazon_cell(n) {
+            return Err(GameError::InputInvalidPosition);
+        }
+
+        if self.get_cell(player_input.to).is_none() || self.get_cell(player_input.arrow).is_none() {
+            return Err(GameError::InputOutOfBounds);
         }
 
         if player_input.from == player_input.to {
             return Err(GameError::InputInvalidPosition);
         }
 
-        let (dir_x, dir_y) =
-            get_dir(player_input.from, player_input.to).ok_or(GameError::InvalidTravel)?;
-        assert!(dir_x != 0 || dir_y != 0);
-
-        let mut from_pos = player_input.from;
-        while from_pos != player_input.to && let Some(new_pos) = from_pos.shift(dir_x, dir_y) {
-            if new_pos == from_pos {
-                return Err(GameError::InvalidTravel);
-            }
-            if *self.get_cell(new_pos).ok_or(GameError::InvalidTravel)? != GameCell::Empty {
-                return Err(GameError::InvalidTravel);
-            }
-            from_pos = new_pos;
+        if !self.is_line_free(player_input.from, player_input.to, player_input.from) {
+            return Err(GameError::InvalidTravel);
         }
 
-        let (dir_x, dir_y) =
-            get_dir(player_input.to, player_input.arrow).ok_or(GameError::InvalidArrowTravel)?;
-        assert!(dir_x != 0 || dir_y != 0);
-
-        let mut to_pos = player_input.arrow;
-        while to_pos != player_input.arrow && let Some(new_pos) = from_pos.shift(dir_x, dir_y) {
-            if new_pos == to_pos {
-                return Err(GameError::InvalidArrowTravel);
-            }
-            if *self
-                .get_cell(new_pos)
-                .ok_or(GameError::InvalidArrowTravel)?
-                != GameCell::Empty
-            {
-                return Err(GameError::InvalidArrowTravel);
-            }
-            to_pos = new_pos;
+        // The amazon has left its cell, so the arrow can fly through (or land on) it
+        if !self.is_line_free(player_input.to, player_input.arrow, player_input.from) {
+            return Err(GameError::InvalidArrowTravel);
         }
 
         *self
@@ -193,20 +161,105 @@ impl DiscordDuelGame for AmazonsGame {
             .ok_or(GameError::InputOutOfBounds)? = GameCell::Empty;
         *self
             .get_mut_cell(player_input.to)
-            .ok_or(GameError::InputOutOfBounds)? = if n == PlayerTurn::Player1 {
-            GameCell::Amazon1
-        } else {
-            GameCell::Amazon2
-        };
+            .ok_or(GameError::InputOutOfBounds)? = amazon_cell(n);
         *self
             .get_mut_cell(player_input.arrow)
             .ok_or(GameError::InputOutOfBounds)? = GameCell::Arrow;
 
-        Ok(false)
+        // The player who cannot move any amazon loses
+        if self.can_move(n.next()) {
+            Ok(GameOutcome::Ongoing)
+        } else {
+            Ok(GameOutcome::Win(n))
+        }
     }
 }
 
 impl AmazonsGame {
+    // Empty grid with the given queens, used to reproduce a recorded game
+    pub fn from_queens(
+        width: usize,
+        height: usize,
+        queens1: &[Pos],
+        queens2: &[Pos],
+    ) -> Result<Self, GameError> {
+        let mut game = Self(vec![vec![GameCell::Empty; height]; width]);
+        for (queens, cell) in [(queens1, GameCell::Amazon1), (queens2, GameCell::Amazon2)] {
+            for pos in queens {
+                let game_cell = game.get_mut_cell(*pos).ok_or(GameError::InputOutOfBounds)?;
+                if *game_cell != GameCell::Empty {
+                    return Err(GameError::InputInvalidPosition);
+                }
+                *game_cell = cell;
+            }
+        }
+        Ok(game)
+    }
+
+    // Rows of console symbols (seen by player 1) separated by '/' or new lines,
+    // spaces are ignored: "*_+__/_____/__@__/_____/_____"
+    pub fn from_board(board: &str) -> Result<Self, anyhow::Error> {
+        let grid = board
+            .split(['/', '\n'])
+            .map(|row| {
+                row.chars()
+                    .filter(|c| !c.is_whitespace())
+                    .map(|c| match c {
+                        '_' => Ok(GameCell::Empty),
+                        '*' => Ok(GameCell::Amazon1),
+                        '+' => Ok(GameCell::Amazon2),
+                        '@' => Ok(GameCell::Arrow),
+                        _ => Err(anyhow::Error::msg(format!("Invalid board symbol: {}", c))),
+                    })
+                    .collect::<Result<Vec<GameCell>, anyhow::Error>>()
+            })
+            .filter(|row| !matches!(row, Ok(cells) if cells.is_empty()))
+            .collect::<Result<Vec<Vec<GameCell>>, anyhow::Error>>()?;
+
+        let sizes = AmazonsConfig::MIN_SIZE..=AmazonsConfig::MAX_SIZE;
+        let height = grid.first().map_or(0, Vec::len);
+        if !sizes.contains(&grid.len()) || !sizes.contains(&height) {
+            return Err(anyhow::Error::msg(format!(
+                "Board should be between {}x{} and {}x{}",
+                sizes.start(),
+                sizes.start(),
+                sizes.end(),
+                sizes.end()
+            )));
+        }
+        if grid.iter().any(|row| row.len() != height) {
+            return Err(anyhow::Error::msg("Board rows should have the same length"));
+        }
+
+        let game = Self(grid);
+        let queens1 = game.amazons(PlayerTurn::Player1).count();
+        let queens2 = game.amazons(PlayerTurn::Player2).count();
+        let queens = AmazonsConfig::MIN_QUEENS..=AmazonsConfig::MAX_QUEENS;
+        if queens1 != queens2 || !queens.contains(&queens1) {
+            return Err(anyhow::Error::msg(format!(
+                "Each player should have the same number of queens ({}-{})",
+                queens.start(),
+                queens.end()
+            )));
+        }
+        if !game.can_move(PlayerTurn::Player1) || !game.can_move(PlayerTurn::Player2) {
+            return Err(anyhow::Error::msg("Board is already over"));
+        }
+        Ok(game)
+    }
+
+    pub fn to_board(&self) -> String {
+        self.to_console_player1()
+            .split('\n')
+            .map(|row| row.replace(' ', ""))
+            .collect::<Vec<String>>()
+            .join("/")
+    }
+
+    pub fn queens(&self, n: PlayerTurn) -> Vec<Pos> {
+        self.amazons(n).collect()
+    }
+
     fn get_cell(&self, pos: Pos) -> Option<&GameCell> {
         self.0.get(pos.x)?.get(pos.y)
     }
@@ -214,6 +267,93 @@ impl AmazonsGame {
     fn get_mut_cell(&mut self, pos: Pos) -> Option<&mut GameCell> {
         self.0.get_mut(pos.x)?.get_mut(pos.y)
     }
+
+    // Check that `end` is reached from `start` in a queen line of empty cells
+    // (the `vacated` cell counts as empty)
+    fn is_line_free(&self, start: Pos, end: Pos, vacated: Pos) -> bool {
+        let Some((dir_x, dir_y)) = get_dir(start, end) else {
+            return false;
+        };
+
+        let mut pos = start;
+        while pos != end {
+            pos = match pos.shift(dir_x, dir_y) {
+                Some(new_pos) => new_pos,
+                None => return false,
+            };
+            if pos != vacated && self.get_cell(pos) != Some(&GameCell::Empty) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn amazons(&self, n: PlayerTurn) -> impl Iterator<Item = Pos> + '_ {
+        let amazon = amazon_cell(n);
+        self.0.iter().enumerate().flat_map(move |(x, column)| {
+            column
+                .iter()
+                .enumerate()
+                .filter(move |(_, cell)| **cell == amazon)
+                .map(move |(y, _)| Pos { x, y })
+        })
+    }
+
+    // An amazon next to an empty cell can always move there and shoot back where it was
+    pub fn can_move(&self, n: PlayerTurn) -> bool {
+        self.amazons(n).any(|from| {
+            DIRECTIONS.iter().any(|&(dx, dy)| {
+                from.shift(dx, dy)
+                    .and_then(|pos| self.get_cell(pos))
+                    .is_some_and(|cell| *cell == GameCell::Empty)
+            })
+        })
+    }
+
+    pub fn legal_moves(&self, n: PlayerTurn) -> Vec<PlayerInput> {
+        let mut moves = Vec::new();
+        for from in self.amazons(n) {
+            for to in self.queen_reach(from, from) {
+                for arrow in self.queen_reach(to, from) {
+                    moves.push(PlayerInput { from, to, arrow });
+                }
+            }
+        }
+        moves
+    }
+
+    // Every position reachable in a queen line from `start` (the `vacated` cell counts as empty)
+    fn queen_reach(&self, start: Pos, vacated: Pos) -> Vec<Pos> {
+        let mut reach = Vec::new();
+        for (dx, dy) in DIRECTIONS {
+            let mut pos = start;
+            while let Some(new_pos) = pos.shift(dx, dy)
+                && (new_pos == vacated || self.get_cell(new_pos) == Some(&GameCell::Empty))
+            {
+                reach.push(new_pos);
+                pos = new_pos;
+            }
+        }
+        reach
+    }
+}
+
+const DIRECTIONS: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+fn amazon_cell(n: PlayerTurn) -> GameCell {
+    match n {
+        PlayerTurn::Player1 => GameCell::Amazon1,
+        PlayerTurn::Player2 => GameCell::Amazon2,
+    }
 }
 
 fn get_dir(from: Pos, to: Pos) -> Option<(isize, isize)> {
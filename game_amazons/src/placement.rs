@@ -0,0 +1,121 @@
+use crate::pos::Pos;
+use anyhow::Error;
+use rand::seq::{IteratorRandom, SliceRandom};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// How the queens are placed at the start of the game, both players always get the same number
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub enum Placement {
+    // Any free cell
+    Random,
+    // Standard 10x10 setup with 4 queens
+    Classic,
+    // Player 2 queens are the reflection of player 1 queens across the middle of the height
+    Mirror,
+    // Player 2 queens are the reflection of player 1 queens through the center of the grid
+    PointSymmetric,
+}
+
+impl Placement {
+    pub const ALL: [Placement; 4] = [
+        Placement::Random,
+        Placement::Classic,
+        Placement::Mirror,
+        Placement::PointSymmetric,
+    ];
+
+    pub const CLASSIC_SIZE: usize = 10;
+    pub const CLASSIC_QUEENS: usize = 4;
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Placement::Random => "random",
+            Placement::Classic => "classic",
+            Placement::Mirror => "mirror",
+            Placement::PointSymmetric => "point_symmetric",
+        }
+    }
+
+    // Queens of player 1 and player 2, never on the same cell
+    pub fn queens<R: Rng>(
+        &self,
+        width: usize,
+        height: usize,
+        queens: usize,
+        rng: &mut R,
+    ) -> (Vec<Pos>, Vec<Pos>) {
+        let cells = (0..width).flat_map(|x| (0..height).map(move |y| Pos { x, y }));
+        match self {
+            Placement::Random => {
+                let mut chosen = cells.choose_multiple(rng, 2 * queens);
+                chosen.shuffle(rng);
+                let queens2 = chosen.split_off(chosen.len() / 2);
+                (chosen, queens2)
+            }
+            Placement::Classic => {
+                let last = Placement::CLASSIC_SIZE - 1;
+                let queens1 = vec![
+                    Pos { x: 0, y: 3 },
+                    Pos { x: 3, y: 0 },
+                    Pos { x: 6, y: 0 },
+                    Pos { x: last, y: 3 },
+                ];
+                let queens2 = queens1
+                    .iter()
+                    .map(|pos| Pos {
+                        x: pos.x,
+                        y: last - pos.y,
+                    })
+                    .collect();
+                (queens1, queens2)
+            }
+            Placement::Mirror => {
+                let queens1 = cells
+                    .filter(|pos| pos.y < height / 2)
+                    .choose_multiple(rng, queens);
+                let queens2 = queens1
+                    .iter()
+                    .map(|pos| Pos {
+                        x: pos.x,
+                        y: height - 1 - pos.y,
+                    })
+                    .collect();
+                (queens1, queens2)
+            }
+            Placement::PointSymmetric => {
+                // Only the cells before the center, so a queen is never its own reflection
+                let queens1 = cells
+                    .filter(|pos| 2 * (pos.x * height + pos.y) + 1 < width * height)
+                    .choose_multiple(rng, queens);
+                let queens2 = queens1
+                    .iter()
+                    .map(|pos| Pos {
+                        x: width - 1 - pos.x,
+                        y: height - 1 - pos.y,
+                    })
+                    .collect();
+                (queens1, queens2)
+            }
+        }
+    }
+}
+
+impl Display for Placement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Placement {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Placement::ALL
+            .into_iter()
+            .find(|placement| placement.name() == s.trim())
+            .ok_or(Error::msg(format!("Unknown placement: {}", s)))
+    }
+}
@@ -1,4 +1,7 @@
-#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
 pub struct Pos {
     pub x: usize,
     pub y: usize,
@@ -22,3 +25,9 @@ impl Pos {
         })
     }
 }
+
+impl Display for Pos {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({},{})", self.x, self.y)
+    }
+}
@@ -0,0 +1,243 @@
+use crate::config::AmazonsConfig;
+use crate::input::PlayerInput;
+use crate::placement::Placement;
+use crate::pos::Pos;
+use crate::AmazonsGame;
+use anyhow::Error;
+use duel_game::{DuelGame, GameOutcome, PlayerTurn};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// A whole match in plain text: a header with the config and the initial queens,
+// a blank line, then one move per line in the program output notation
+//
+// # Amazons
+// Width: 8
+// Height: 8
+// Queens: 2
+// Placement: mirror
+// Seed: 42
+// Board: *____/... (only for a custom starting board, which may hold arrows)
+// Queens1: (0,2) (5,7)
+// Queens2: (7,5) (2,0)
+//
+// (0,2)|(0,5)|(3,5)
+// (7,5)|(4,5)|(4,1)
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct AmazonsRecord {
+    pub config: AmazonsConfig,
+    pub queens1: Vec<Pos>,
+    pub queens2: Vec<Pos>,
+    pub moves: Vec<PlayerInput>,
+}
+
+impl AmazonsRecord {
+    const WIDTH_KEY: &'static str = "Width";
+    const HEIGHT_KEY: &'static str = "Height";
+    const QUEENS_KEY: &'static str = "Queens";
+    const PLACEMENT_KEY: &'static str = "Placement";
+    const SEED_KEY: &'static str = "Seed";
+    const BOARD_KEY: &'static str = "Board";
+    const QUEENS1_KEY: &'static str = "Queens1";
+    const QUEENS2_KEY: &'static str = "Queens2";
+
+    // Start a record from a freshly created game
+    pub fn new(config: AmazonsConfig, game: &AmazonsGame) -> Self {
+        Self {
+            config,
+            queens1: game.queens(PlayerTurn::Player1),
+            queens2: game.queens(PlayerTurn::Player2),
+            moves: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, player_input: PlayerInput) {
+        self.moves.push(player_input);
+    }
+
+    pub fn initial_game(&self) -> Result<AmazonsGame, Error> {
+        if let Some(board) = &self.config.board {
+            return AmazonsGame::from_board(board);
+        }
+        Ok(AmazonsGame::from_queens(
+            self.config.width,
+            self.config.height,
+            &self.queens1,
+            &self.queens2,
+        )?)
+    }
+
+    // Play every move from the initial game, player 1 starts
+    pub fn replay(&self) -> Result<(AmazonsGame, GameOutcome), Error> {
+        let mut game = self.initial_game()?;
+        let mut outcome = GameOutcome::Ongoing;
+        let mut n = PlayerTurn::Player1;
+        for (index, player_input) in self.moves.iter().enumerate() {
+            if outcome != GameOutcome::Ongoing {
+                return Err(Error::msg(format!(
+                    "Move {} ({}) is played after the end of the game",
+                    index + 1,
+                    player_input
+                )));
+            }
+            outcome = game.play(*player_input, n).map_err(|why| {
+                Error::msg(format!("Move {} ({}): {}", index + 1, player_input, why))
+            })?;
+            n = n.next();
+        }
+        Ok((game, outcome))
+    }
+}
+
+impl Display for AmazonsRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let queens_str = |queens: &[Pos]| {
+            queens
+                .iter()
+                .map(|pos| pos.to_string())
+                .collect::<Vec<String>>()
+                .join(" ")
+        };
+        writeln!(f, "# Amazons")?;
+        writeln!(f, "{}: {}", AmazonsRecord::WIDTH_KEY, self.config.width)?;
+        writeln!(f, "{}: {}", AmazonsRecord::HEIGHT_KEY, self.config.height)?;
+        writeln!(f, "{}: {}", AmazonsRecord::QUEENS_KEY, self.config.queens)?;
+        writeln!(
+            f,
+            "{}: {}",
+            AmazonsRecord::PLACEMENT_KEY,
+            self.config.placement
+        )?;
+        writeln!(f, "{}: {}", AmazonsRecord::SEED_KEY, self.config.seed)?;
+        if let Some(board) = &self.config.board {
+            writeln!(f, "{}: {}", AmazonsRecord::BOARD_KEY, board)?;
+        }
+        writeln!(
+            f,
+            "{}: {}",
+            AmazonsRecord::QUEENS1_KEY,
+            queens_str(&self.queens1)
+        )?;
+        writeln!(
+            f,
+            "{}: {}",
+            AmazonsRecord::QUEENS2_KEY,
+            queens_str(&self.queens2)
+        )?;
+        writeln!(f)?;
+        for player_input in self.moves.iter() {
+            writeln!(f, "{}", player_input)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for AmazonsRecord {
+    type Err = Error;
+
+    // Lines starting with '#' are comments
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.starts_with('#'));
+
+        let mut width = None;
+        let mut height = None;
+        let mut queens = None;
+        let mut placement = None;
+        let mut seed = None;
+        let mut board = None;
+        let mut queens1 = None;
+        let mut queens2 = None;
+        let mut in_header = false;
+        for line in lines.by_ref() {
+            if line.is_empty() {
+                if in_header {
+                    break;
+                }
+                continue;
+            }
+            in_header = true;
+            let (key, value) = line
+                .split_once(':')
+                .ok_or(Error::msg(format!("Invalid header line: {}", line)))?;
+            let value = value.trim();
+            match key.trim() {
+                AmazonsRecord::WIDTH_KEY => width = Some(parse_number(key, value)?),
+                AmazonsRecord::HEIGHT_KEY => height = Some(parse_number(key, value)?),
+                AmazonsRecord::QUEENS_KEY => queens = Some(parse_number(key, value)?),
+                AmazonsRecord::PLACEMENT_KEY => placement = Some(Placement::from_str(value)?),
+                AmazonsRecord::SEED_KEY => seed = Some(parse_number(key, value)?),
+                AmazonsRecord::BOARD_KEY => board = Some(value.to_string()),
+                AmazonsRecord::QUEENS1_KEY => queens1 = Some(parse_queens(value)?),
+                AmazonsRecord::QUEENS2_KEY => queens2 = Some(parse_queens(value)?),
+                _ => return Err(Error::msg(format!("Unknown header: {}", key))),
+            }
+        }
+
+        let missing = |key: &str| Error::msg(format!("Missing header: {}", key));
+        let config = AmazonsConfig {
+            width: width.ok_or(missing(AmazonsRecord::WIDTH_KEY))?,
+            height: height.ok_or(missing(AmazonsRecord::HEIGHT_KEY))?,
+            queens: queens.ok_or(missing(AmazonsRecord::QUEENS_KEY))?,
+            placement: placement.ok_or(missing(AmazonsRecord::PLACEMENT_KEY))?,
+            seed: seed.ok_or(missing(AmazonsRecord::SEED_KEY))?,
+            board,
+        };
+        let sizes = AmazonsConfig::MIN_SIZE..=AmazonsConfig::MAX_SIZE;
+        if !sizes.contains(&config.width) || !sizes.contains(&config.height) {
+            return Err(Error::msg(format!(
+                "Board should be between {}x{} and {}x{}",
+                sizes.start(),
+                sizes.start(),
+                sizes.end(),
+                sizes.end()
+            )));
+        }
+        let queens = AmazonsConfig::MIN_QUEENS..=AmazonsConfig::MAX_QUEENS;
+        if !queens.contains(&config.queens) {
+            return Err(Error::msg(format!(
+                "Each player should have {}-{} queens",
+                queens.start(),
+                queens.end()
+            )));
+        }
+        let queens1 = queens1.ok_or(missing(AmazonsRecord::QUEENS1_KEY))?;
+        let queens2 = queens2.ok_or(missing(AmazonsRecord::QUEENS2_KEY))?;
+        if queens1.len() != config.queens || queens2.len() != config.queens {
+            return Err(Error::msg(format!(
+                "Each player should have {} queens",
+                config.queens
+            )));
+        }
+
+        let moves = lines
+            .filter(|line| !line.is_empty())
+            .map(PlayerInput::from_str)
+            .collect::<Result<Vec<PlayerInput>, Error>>()?;
+
+        Ok(Self {
+            config,
+            queens1,
+            queens2,
+            moves,
+        })
+    }
+}
+
+fn parse_number<T: FromStr>(key: &str, value: &str) -> Result<T, Error> {
+    value
+        .parse()
+        .map_err(|_| Error::msg(format!("Invalid {}: {}", key.trim(), value)))
+}
+
+// Should be in the form of "(0,2) (5,7)"
+fn parse_queens(value: &str) -> Result<Vec<Pos>, Error> {
+    value
+        .split_whitespace()
+        .map(|pos_str| {
+            Pos::parse_pos(pos_str).ok_or(Error::msg(format!("Invalid position: {}", pos_str)))
+        })
+        .collect()
+}
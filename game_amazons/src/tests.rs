@@ -0,0 +1,504 @@
+use crate::config::AmazonsConfig;
+use crate::input::PlayerInput;
+use crate::placement::Placement;
+use crate::pos::Pos;
+use crate::record::AmazonsRecord;
+use crate::{AmazonsGame, GameCell, GameError};
+use duel_game::{DuelGame, GameConfig, GameOption, GameOutcome, OptionValue, PlayerTurn};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const BOARDS: usize = 300;
+const MOVES_PER_BOARD: usize = 200;
+
+fn random_board(rng: &mut StdRng, width: usize, height: usize) -> Vec<Vec<GameCell>> {
+    (0..width)
+        .map(|_| {
+            (0..height)
+                .map(|_| match rng.gen_range(0..10) {
+                    0 => GameCell::Amazon1,
+                    1 => GameCell::Amazon2,
+                    2 | 3 => GameCell::Arrow,
+                    _ => GameCell::Empty,
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn random_pos(rng: &mut StdRng, grid: &[Vec<GameCell>]) -> Pos {
+    // Sometimes one step out of the board
+    Pos {
+        x: rng.gen_range(0..=grid.len()),
+        y: rng.gen_range(0..=grid[0].len()),
+    }
+}
+
+fn random_move(rng: &mut StdRng, grid: &[Vec<GameCell>], n: PlayerTurn) -> PlayerInput {
+    let amazon = amazon(n);
+    let own: Vec<Pos> = all_positions(grid)
+        .filter(|pos| grid[pos.x][pos.y] == amazon)
+        .collect();
+    let from = if !own.is_empty() && rng.gen_bool(0.8) {
+        own[rng.gen_range(0..own.len())]
+    } else {
+        random_pos(rng, grid)
+    };
+    PlayerInput {
+        from,
+        to: random_pos(rng, grid),
+        arrow: random_pos(rng, grid),
+    }
+}
+
+fn amazon(n: PlayerTurn) -> GameCell {
+    match n {
+        PlayerTurn::Player1 => GameCell::Amazon1,
+        PlayerTurn::Player2 => GameCell::Amazon2,
+    }
+}
+
+fn all_positions(grid: &[Vec<GameCell>]) -> impl Iterator<Item = Pos> + '_ {
+    (0..grid.len()).flat_map(move |x| (0..grid[x].len()).map(move |y| Pos { x, y }))
+}
+
+fn cell(grid: &[Vec<GameCell>], x: isize, y: isize) -> Option<GameCell> {
+    let column = grid.get(usize::try_from(x).ok()?)?;
+    column.get(usize::try_from(y).ok()?).copied()
+}
+
+// Brute force: walk every cell from `start` to `end` included, which must all be empty
+fn reference_free_line(grid: &[Vec<GameCell>], start: Pos, end: Pos) -> bool {
+    let dx = end.x as isize - start.x as isize;
+    let dy = end.y as isize - start.y as isize;
+    let aligned = dx == 0 || dy == 0 || dx.abs() == dy.abs();
+    let distance = dx.abs().max(dy.abs());
+    if !aligned || distance == 0 {
+        return false;
+    }
+    (1..=distance).all(|k| {
+        let x = start.x as isize + k * dx.signum();
+        let y = start.y as isize + k * dy.signum();
+        cell(grid, x, y) == Some(GameCell::Empty)
+    })
+}
+
+// Return the board after the move, or None if the move is illegal
+fn reference_play(
+    grid: &[Vec<GameCell>],
+    input: PlayerInput,
+    n: PlayerTurn,
+) -> Option<Vec<Vec<GameCell>>> {
+    let from = cell(grid, input.from.x as isize, input.from.y as isize)?;
+    if from != amazon(n) || !reference_free_line(grid, input.from, input.to) {
+        return None;
+    }
+    let mut grid = grid.to_vec();
+    grid[input.from.x][input.from.y] = GameCell::Empty;
+    if !reference_free_line(&grid, input.to, input.arrow) {
+        return None;
+    }
+    grid[input.to.x][input.to.y] = amazon(n);
+    grid[input.arrow.x][input.arrow.y] = GameCell::Arrow;
+    Some(grid)
+}
+
+fn reference_legal_moves(grid: &[Vec<GameCell>], n: PlayerTurn) -> Vec<PlayerInput> {
+    let mut moves = Vec::new();
+    for from in all_positions(grid) {
+        for to in all_positions(grid) {
+            for arrow in all_positions(grid) {
+                let input = PlayerInput { from, to, arrow };
+                if reference_play(grid, input, n).is_some() {
+                    moves.push(input);
+                }
+            }
+        }
+    }
+    moves
+}
+
+fn sorted(mut moves: Vec<PlayerInput>) -> Vec<(usize, usize, usize, usize, usize, usize)> {
+    let mut keys: Vec<_> = moves
+        .drain(..)
+        .map(|m| (m.from.x, m.from.y, m.to.x, m.to.y, m.arrow.x, m.arrow.y))
+        .collect();
+    keys.sort_unstable();
+    keys
+}
+
+fn turn(rng: &mut StdRng) -> PlayerTurn {
+    if rng.gen_bool(0.5) {
+        PlayerTurn::Player1
+    } else {
+        PlayerTurn::Player2
+    }
+}
+
+#[test]
+fn play_matches_reference_on_random_moves() {
+    let mut rng = StdRng::seed_from_u64(0x00A1_1A20);
+    for _ in 0..BOARDS {
+        let (width, height) = (rng.gen_range(5..=8), rng.gen_range(5..=8));
+        let grid = random_board(&mut rng, width, height);
+        for _ in 0..MOVES_PER_BOARD {
+            let n = turn(&mut rng);
+            let input = random_move(&mut rng, &grid, n);
+            let mut game = AmazonsGame(grid.clone());
+            let result = game.play(input, n);
+            match reference_play(&grid, input, n) {
+                Some(expected) => {
+                    let outcome = result.unwrap_or_else(|why| {
+                        panic!(
+                            "{:?} rejected ({}) on\n{}",
+                            input,
+                            why,
+                            game.to_console_player1()
+                        )
+                    });
+                    assert_eq!(game.0, expected, "{:?}", input);
+                    let stuck = reference_legal_moves(&expected, n.next()).is_empty();
+                    let expected_outcome = if stuck {
+                        GameOutcome::Win(n)
+                    } else {
+                        GameOutcome::Ongoing
+                    };
+                    assert_eq!(outcome, expected_outcome, "{:?}", input);
+                }
+                None => {
+                    assert!(result.is_err(), "{:?} accepted", input);
+                    assert_eq!(game.0, grid, "rejected move changed the board");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn legal_moves_match_reference() {
+    let mut rng = StdRng::seed_from_u64(0x0000_BEE5);
+    for _ in 0..BOARDS / 10 {
+        let (width, height) = (rng.gen_range(5..=6), rng.gen_range(5..=6));
+        let grid = random_board(&mut rng, width, height);
+        let game = AmazonsGame(grid.clone());
+        for n in [PlayerTurn::Player1, PlayerTurn::Player2] {
+            let moves = game.legal_moves(n);
+            assert_eq!(game.can_move(n), !moves.is_empty());
+            assert_eq!(sorted(moves), sorted(reference_legal_moves(&grid, n)));
+        }
+    }
+}
+
+#[test]
+fn every_legal_move_is_accepted() {
+    let mut rng = StdRng::seed_from_u64(0x00C0_A1A5);
+    for _ in 0..BOARDS {
+        let (width, height) = (rng.gen_range(5..=10), rng.gen_range(5..=10));
+        let grid = random_board(&mut rng, width, height);
+        let n = turn(&mut rng);
+        for input in AmazonsGame(grid.clone()).legal_moves(n) {
+            let mut game = AmazonsGame(grid.clone());
+            assert!(game.play(input, n).is_ok(), "{:?} rejected", input);
+        }
+    }
+}
+
+fn board(rows: &[&str]) -> AmazonsGame {
+    AmazonsGame(
+        rows.iter()
+            .map(|row| {
+                row.chars()
+                    .map(|c| match c {
+                        '*' => GameCell::Amazon1,
+                        '+' => GameCell::Amazon2,
+                        '@' => GameCell::Arrow,
+                        _ => GameCell::Empty,
+                    })
+                    .collect()
+            })
+            .collect(),
+    )
+}
+
+fn input(from: (usize, usize), to: (usize, usize), arrow: (usize, usize)) -> PlayerInput {
+    PlayerInput {
+        from: Pos {
+            x: from.0,
+            y: from.1,
+        },
+        to: Pos { x: to.0, y: to.1 },
+        arrow: Pos {
+            x: arrow.0,
+            y: arrow.1,
+        },
+    }
+}
+
+#[test]
+fn arrow_cannot_fly_through_a_queen() {
+    let mut game = board(&["*_+__", "_____", "_____", "_____", "_____"]);
+    let result = game.play(input((0, 0), (1, 0), (1, 4)), PlayerTurn::Player1);
+    assert!(result.is_ok());
+    let result = game.play(input((0, 2), (0, 1), (0, 4)), PlayerTurn::Player2);
+    assert!(result.is_ok());
+    let result = game.play(input((1, 0), (0, 0), (0, 3)), PlayerTurn::Player1);
+    assert!(matches!(result, Err(GameError::InvalidArrowTravel)));
+}
+
+#[test]
+fn arrow_cannot_fly_through_a_wall() {
+    let mut game = board(&["*_@__", "_____", "_____", "_____", "+____"]);
+    let result = game.play(input((0, 0), (1, 0), (1, 4)), PlayerTurn::Player1);
+    assert!(result.is_ok());
+    let result = game.play(input((1, 0), (0, 0), (0, 3)), PlayerTurn::Player1);
+    assert!(matches!(result, Err(GameError::InvalidArrowTravel)));
+}
+
+#[test]
+fn arrow_can_fly_through_the_vacated_cell() {
+    let mut game = board(&["_*___", "_____", "_____", "_____", "+____"]);
+    assert!(game
+        .play(input((0, 1), (0, 2), (0, 0)), PlayerTurn::Player1)
+        .is_ok());
+    assert_eq!(game.0[0][0], GameCell::Arrow);
+    assert_eq!(game.0[0][1], GameCell::Empty);
+    assert_eq!(game.0[0][2], GameCell::Amazon1);
+}
+
+#[test]
+fn game_ends_when_the_opponent_is_boxed_in() {
+    let mut game = board(&["+@___", "@____", "_____", "___*_", "_____"]);
+    let result = game.play(input((3, 3), (1, 1), (4, 4)), PlayerTurn::Player1);
+    assert!(matches!(result, Ok(GameOutcome::Win(PlayerTurn::Player1))));
+}
+
+#[test]
+fn serde_round_trip() {
+    let mut game = board(&["*_+__", "_____", "_____", "_____", "_____"]);
+    let player_input = input((0, 0), (1, 0), (1, 4));
+    game.play(player_input, PlayerTurn::Player1).unwrap();
+
+    let json = serde_json::to_string(&game).unwrap();
+    let loaded: AmazonsGame = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.0, game.0);
+
+    let json = serde_json::to_string(&player_input).unwrap();
+    assert_eq!(
+        json,
+        r#"{"from":{"x":0,"y":0},"to":{"x":1,"y":0},"arrow":{"x":1,"y":4}}"#
+    );
+    assert_eq!(
+        serde_json::from_str::<PlayerInput>(&json).unwrap(),
+        player_input
+    );
+
+    let config = AmazonsConfig {
+        width: 7,
+        height: 9,
+        queens: 3,
+        placement: Placement::Mirror,
+        seed: 42,
+        board: None,
+    };
+    let loaded: AmazonsConfig =
+        serde_json::from_value(serde_json::to_value(&config).unwrap()).unwrap();
+    assert_eq!(loaded.to_string(), config.to_string());
+}
+
+#[test]
+fn record_round_trip_and_replay() {
+    let text = "# Amazons\nWidth: 5\nHeight: 5\nQueens: 2\nPlacement: random\nSeed: 7\nQueens1: (0,0) (4,4)\nQueens2: (0,2) (4,2)\n\n(0,0)|(1,0)|(1,4)\n(0,2)|(0,1)|(0,4)\n";
+    let record: AmazonsRecord = text.parse().unwrap();
+    assert_eq!(record.moves.len(), 2);
+    assert_eq!(record.to_string(), text);
+
+    let (game, outcome) = record.replay().unwrap();
+    assert_eq!(outcome, GameOutcome::Ongoing);
+    assert_eq!(
+        game.0,
+        board(&["_+__@", "*___@", "_____", "_____", "__+_*"]).0
+    );
+
+    let mut written = AmazonsRecord::new(record.config.clone(), &record.initial_game().unwrap());
+    for player_input in record.moves.iter() {
+        written.push(*player_input);
+    }
+    assert_eq!(written, record);
+}
+
+#[test]
+fn record_rejects_invalid_games() {
+    let header = "Width: 5\nHeight: 5\nQueens: 2\nPlacement: random\nSeed: 7\nQueens1: (0,0) (4,4)\nQueens2: (0,2) (4,2)\n\n";
+    assert!("Width: 5\nHeight: 5\n".parse::<AmazonsRecord>().is_err());
+    assert!(format!("{}(0,0)|(1,0)\n", header)
+        .parse::<AmazonsRecord>()
+        .is_err());
+
+    // The second move is played by player 2, not with a queen of player 1
+    let record: AmazonsRecord = format!("{}(0,0)|(1,0)|(1,4)\n(1,0)|(2,0)|(3,0)\n", header)
+        .parse()
+        .unwrap();
+    let Err(error) = record.replay() else {
+        panic!("Replay should fail");
+    };
+    assert!(error.to_string().starts_with("Move 2 ((1,0)|(2,0)|(3,0))"));
+
+    let record: AmazonsRecord =
+        "Width: 5\nHeight: 5\nQueens: 2\nPlacement: random\nSeed: 7\nQueens1: (0,0) (4,4)\nQueens2: (0,0) (4,2)\n"
+            .parse()
+            .unwrap();
+    assert!(record.initial_game().is_err());
+
+    // The header is checked against the limits of the config before any board is built
+    assert!(header
+        .replace("Width: 5", "Width: 100000")
+        .parse::<AmazonsRecord>()
+        .is_err());
+    assert!(header
+        .replace("Queens: 2", "Queens: 7")
+        .parse::<AmazonsRecord>()
+        .is_err());
+}
+
+#[test]
+fn same_seed_gives_same_board() {
+    let config = |seed| AmazonsConfig {
+        width: 10,
+        height: 10,
+        queens: 4,
+        placement: Placement::Random,
+        seed,
+        board: None,
+    };
+    assert_eq!(
+        AmazonsGame::new(config(42)).0,
+        AmazonsGame::new(config(42)).0
+    );
+    assert_ne!(
+        AmazonsGame::new(config(42)).0,
+        AmazonsGame::new(config(43)).0
+    );
+    assert!(config(42)
+        .to_string()
+        .ends_with("Placement: random | Seed: 42"));
+}
+
+#[test]
+fn seed_out_of_range_is_refused() {
+    let seed = |value| {
+        AmazonsConfig::from_options(&[GameOption {
+            name: "seed".to_string(),
+            value: OptionValue::Integer(value),
+        }])
+    };
+    assert_eq!(
+        seed(AmazonsConfig::MAX_SEED as i64).unwrap().seed,
+        AmazonsConfig::MAX_SEED
+    );
+    assert_eq!(seed(0).unwrap().seed, 0);
+    assert!(seed(-1).is_err());
+    assert!(seed(1 << 53).is_err());
+}
+
+#[test]
+fn seed_board_is_pinned() {
+    // A published seed has to give this board whatever the version of rand
+    let game = AmazonsGame::new(AmazonsConfig {
+        width: 6,
+        height: 6,
+        queens: 2,
+        placement: Placement::Random,
+        seed: 42,
+        board: None,
+    });
+    assert_eq!(game.to_board(), "*_____/+_____/_+____/______/_____*/______");
+}
+
+#[test]
+fn placements_give_every_queen() {
+    for placement in Placement::ALL {
+        for seed in 0..200 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let width = rng.gen_range(5..=15);
+            let height = rng.gen_range(5..=15);
+            let queens = rng.gen_range(2..=6);
+            let (width, height, queens) = match placement {
+                Placement::Classic => (10, 10, 4),
+                _ => (width, height, queens),
+            };
+            let game = AmazonsGame::new(AmazonsConfig {
+                width,
+                height,
+                queens,
+                placement,
+                seed,
+                board: None,
+            });
+            let queens1 = game.queens(PlayerTurn::Player1);
+            let queens2 = game.queens(PlayerTurn::Player2);
+            assert_eq!(queens1.len(), queens, "{} seed {}", placement, seed);
+            assert_eq!(queens2.len(), queens, "{} seed {}", placement, seed);
+
+            let reflect = |pos: &Pos| match placement {
+                Placement::Random => None,
+                Placement::Classic | Placement::Mirror => Some(Pos {
+                    x: pos.x,
+                    y: height - 1 - pos.y,
+                }),
+                Placement::PointSymmetric => Some(Pos {
+                    x: width - 1 - pos.x,
+                    y: height - 1 - pos.y,
+                }),
+            };
+            for pos in queens1.iter().filter_map(reflect) {
+                assert!(queens2.contains(&pos), "{} seed {}", placement, seed);
+            }
+        }
+    }
+}
+
+#[test]
+fn board_string_round_trip() {
+    let text = "*__+_/_____/__@__/_____/+__*_";
+    let game = AmazonsGame::from_board(text).unwrap();
+    assert_eq!(
+        game.0,
+        board(&["*__+_", "_____", "__@__", "_____", "+__*_"]).0
+    );
+    assert_eq!(game.to_board(), text);
+    assert_eq!(
+        AmazonsGame::from_board("* _ _ + _\n_ _ _ _ _\n_ _ @ _ _\n_ _ _ _ _\n+ _ _ * _\n")
+            .unwrap()
+            .to_board(),
+        text
+    );
+}
+
+#[test]
+fn board_string_is_validated() {
+    // Too small, uneven rows, unknown symbol, one queen each, different queen counts
+    assert!(AmazonsGame::from_board("*___/____/____/___+").is_err());
+    assert!(AmazonsGame::from_board("*__+_/_____/____/_____/+__*_").is_err());
+    assert!(AmazonsGame::from_board("*__+_/_____/__x__/_____/+__*_").is_err());
+    assert!(AmazonsGame::from_board("*____/_____/_____/_____/____+").is_err());
+    assert!(AmazonsGame::from_board("*__+_/_____/_____/_____/*__*_").is_err());
+    // Player 2 queens are boxed in
+    assert!(AmazonsGame::from_board("+@__*/@@___/_____/_@@@_/_@+@*").is_err());
+}
+
+#[test]
+fn record_keeps_custom_board() {
+    let config = AmazonsConfig {
+        width: 5,
+        height: 5,
+        queens: 2,
+        placement: Placement::Random,
+        seed: 0,
+        board: Some("*__+_/_____/__@__/_____/+__*_".to_string()),
+    };
+    let game = AmazonsGame::new(config.clone());
+    let record = AmazonsRecord::new(config, &game);
+    let loaded: AmazonsRecord = record.to_string().parse().unwrap();
+    assert_eq!(loaded, record);
+    assert_eq!(loaded.initial_game().unwrap().0, game.0);
+}
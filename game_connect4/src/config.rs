@@ -0,0 +1,82 @@
+use duel_game::{ConfigOption, GameConfig, GameOption, OptionKind, OptionValue};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+#[derive(Serialize, Deserialize)]
+pub struct Connect4Config {
+    pub rows: usize,
+    pub columns: usize,
+    pub win_length: usize,
+}
+
+impl Connect4Config {
+    const ROWS_NAME: &'static str = "rows";
+    const COLUMNS_NAME: &'static str = "columns";
+    const WIN_LENGTH_NAME: &'static str = "win_length";
+}
+
+impl GameConfig for Connect4Config {
+    fn options() -> Vec<ConfigOption> {
+        vec![
+            ConfigOption::new(
+                Connect4Config::ROWS_NAME,
+                "Number of rows (4-12)",
+                OptionKind::Integer,
+            ),
+            ConfigOption::new(
+                Connect4Config::COLUMNS_NAME,
+                "Number of columns (4-12)",
+                OptionKind::Integer,
+            ),
+            ConfigOption::new(
+                Connect4Config::WIN_LENGTH_NAME,
+                "Number of aligned discs to win (3-8, at most the longest side)",
+                OptionKind::Integer,
+            ),
+        ]
+    }
+
+    fn from_options(options: &[GameOption]) -> anyhow::Result<Self> {
+        let mut rows = 6;
+        let mut columns = 7;
+        let mut win_length = 4;
+        for option in options.iter() {
+            match option.name.as_str() {
+                Connect4Config::ROWS_NAME => {
+                    if let OptionValue::Integer(rows_desired) = option.value {
+                        rows = rows_desired.clamp(4, 12) as usize;
+                    }
+                }
+                Connect4Config::COLUMNS_NAME => {
+                    if let OptionValue::Integer(columns_desired) = option.value {
+                        columns = columns_desired.clamp(4, 12) as usize;
+                    }
+                }
+                Connect4Config::WIN_LENGTH_NAME => {
+                    if let OptionValue::Integer(win_length_desired) = option.value {
+                        win_length = win_length_desired.clamp(3, 8) as usize;
+                    }
+                }
+                _ => (),
+            }
+        }
+        // A line longer than the board could never be completed
+        let win_length = win_length.min(rows.max(columns));
+
+        Ok(Self {
+            rows,
+            columns,
+            win_length,
+        })
+    }
+}
+
+impl Display for Connect4Config {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Rows: {} | Columns: {} | Win length: {}",
+            self.rows, self.columns, self.win_length
+        )
+    }
+}
@@ -0,0 +1,21 @@
+use anyhow::Error;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct PlayerInput {
+    pub column: usize,
+}
+
+impl FromStr for PlayerInput {
+    type Err = Error;
+
+    // Should be the index of the column, starting from 0
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let column = s
+            .trim()
+            .parse()
+            .map_err(|_| Error::msg(format!("Error parsing output: {}", s)))?;
+        Ok(Self { column })
+    }
+}
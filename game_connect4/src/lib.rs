@@ -0,0 +1,165 @@
+pub mod config;
+pub mod input;
+#[cfg(test)]
+mod tests;
+
+use crate::config::Connect4Config;
+use crate::input::PlayerInput;
+use duel_game::{DuelGame, GameOutcome, PlayerTurn};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub enum GameCell {
+    Empty,
+    Disc1,
+    Disc2,
+}
+
+#[derive(Debug)]
+pub enum GameError {
+    ColumnOutOfBounds,
+    ColumnFull,
+}
+
+impl std::error::Error for GameError {}
+
+impl Display for GameError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameError::ColumnOutOfBounds => write!(f, "Column is out of bounds"),
+            GameError::ColumnFull => write!(f, "Column is full"),
+        }
+    }
+}
+
+// The first row is the top of the grid
+#[derive(Serialize, Deserialize)]
+pub struct Connect4Game {
+    grid: Vec<Vec<GameCell>>,
+    win_length: usize,
+}
+
+impl DuelGame for Connect4Game {
+    type Config = Connect4Config;
+    type Input = PlayerInput;
+    type GameError = GameError;
+
+    fn new(config: Self::Config) -> Self {
+        Self {
+            grid: (0..config.rows)
+                .map(|_| vec![GameCell::Empty; config.columns])
+                .collect(),
+            win_length: config.win_length,
+        }
+    }
+
+    fn to_console_player1(&self) -> String {
+        self.grid
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| match cell {
+                        GameCell::Empty => "_".to_string(),
+                        GameCell::Disc1 => "*".to_string(),
+                        GameCell::Disc2 => "+".to_string(),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn to_console_player2(&self) -> String {
+        self.grid
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| match cell {
+                        GameCell::Empty => "_".to_string(),
+                        GameCell::Disc1 => "+".to_string(),
+                        GameCell::Disc2 => "*".to_string(),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn to_discord(&self) -> String {
+        self.grid
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| match cell {
+                        GameCell::Empty => "⬛".to_string(),
+                        GameCell::Disc1 => "🔴".to_string(),
+                        GameCell::Disc2 => "🟡".to_string(),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn play(
+        &mut self,
+        player_input: Self::Input,
+        n: PlayerTurn,
+    ) -> Result<GameOutcome, Self::GameError> {
+        let column = player_input.column;
+        if column >= self.columns() {
+            return Err(GameError::ColumnOutOfBounds);
+        }
+
+        // The disc falls to the lowest empty cell of the column
+        let row = (0..self.grid.len())
+            .rev()
+            .find(|&row| self.grid[row][column] == GameCell::Empty)
+            .ok_or(GameError::ColumnFull)?;
+        self.grid[row][column] = disc_cell(n);
+
+        if self.is_winning(row, column) {
+            Ok(GameOutcome::Win(n))
+        } else if self.grid[0].iter().all(|cell| *cell != GameCell::Empty) {
+            Ok(GameOutcome::Draw)
+        } else {
+            Ok(GameOutcome::Ongoing)
+        }
+    }
+}
+
+impl Connect4Game {
+    fn columns(&self) -> usize {
+        self.grid.first().map_or(0, Vec::len)
+    }
+
+    fn get_cell(&self, row: isize, column: isize) -> Option<GameCell> {
+        let row = self.grid.get(usize::try_from(row).ok()?)?;
+        row.get(usize::try_from(column).ok()?).copied()
+    }
+
+    // Check the lines going through the last disc played
+    fn is_winning(&self, row: usize, column: usize) -> bool {
+        let disc = self.grid[row][column];
+        let (row, column) = (row as isize, column as isize);
+        let count = |dr: isize, dc: isize| {
+            (1..)
+                .take_while(|&k| self.get_cell(row + k * dr, column + k * dc) == Some(disc))
+                .count()
+        };
+        [(0, 1), (1, 0), (1, 1), (1, -1)]
+            .iter()
+            .any(|&(dr, dc)| 1 + count(dr, dc) + count(-dr, -dc) >= self.win_length)
+    }
+}
+
+fn disc_cell(n: PlayerTurn) -> GameCell {
+    match n {
+        PlayerTurn::Player1 => GameCell::Disc1,
+        PlayerTurn::Player2 => GameCell::Disc2,
+    }
+}
@@ -0,0 +1,82 @@
+use crate::config::Connect4Config;
+use crate::input::PlayerInput;
+use crate::{Connect4Game, GameError};
+use duel_game::{DuelGame, GameConfig, GameOption, GameOutcome, PlayerTurn};
+use std::str::FromStr;
+
+fn game(rows: usize, columns: usize, win_length: usize) -> Connect4Game {
+    Connect4Game::new(Connect4Config {
+        rows,
+        columns,
+        win_length,
+    })
+}
+
+// Play the columns alternately from Player1 and return the last outcome
+fn play_all(game: &mut Connect4Game, columns: &[usize]) -> GameOutcome {
+    let mut n = PlayerTurn::Player1;
+    let mut outcome = GameOutcome::Ongoing;
+    for &column in columns {
+        assert_eq!(outcome, GameOutcome::Ongoing);
+        outcome = game.play(PlayerInput { column }, n).unwrap();
+        n = n.next();
+    }
+    outcome
+}
+
+#[test]
+fn vertical_win() {
+    let mut game = game(6, 7, 4);
+    let outcome = play_all(&mut game, &[0, 1, 0, 1, 0, 1, 0]);
+    assert_eq!(outcome, GameOutcome::Win(PlayerTurn::Player1));
+}
+
+#[test]
+fn horizontal_win() {
+    let mut game = game(6, 7, 4);
+    let outcome = play_all(&mut game, &[0, 1, 0, 2, 5, 3, 5, 4]);
+    assert_eq!(outcome, GameOutcome::Win(PlayerTurn::Player2));
+}
+
+#[test]
+fn diagonal_win() {
+    let mut game = game(6, 7, 4);
+    let outcome = play_all(&mut game, &[0, 1, 1, 2, 2, 3, 2, 3, 3, 6, 3]);
+    assert_eq!(outcome, GameOutcome::Win(PlayerTurn::Player1));
+    assert_eq!(
+        game.to_console_player1(),
+        "_ _ _ _ _ _ _\n\
+         _ _ _ _ _ _ _\n\
+         _ _ _ * _ _ _\n\
+         _ _ * * _ _ _\n\
+         _ * * + _ _ _\n\
+         * + + + _ _ +"
+    );
+}
+
+#[test]
+fn full_board_is_a_draw() {
+    let mut game = game(4, 4, 4);
+    let outcome = play_all(&mut game, &[0, 1, 0, 1, 2, 3, 2, 3, 1, 0, 1, 0, 3, 2, 3, 2]);
+    assert_eq!(outcome, GameOutcome::Draw);
+}
+
+#[test]
+fn invalid_columns() {
+    let mut game = game(4, 4, 4);
+    play_all(&mut game, &[0, 0, 0, 0]);
+    let result = game.play(PlayerInput { column: 0 }, PlayerTurn::Player1);
+    assert!(matches!(result, Err(GameError::ColumnFull)));
+    let result = game.play(PlayerInput { column: 4 }, PlayerTurn::Player1);
+    assert!(matches!(result, Err(GameError::ColumnOutOfBounds)));
+}
+
+#[test]
+fn win_length_fits_the_board() {
+    let options = ["rows=4", "columns=5", "win_length=8"]
+        .iter()
+        .map(|option| GameOption::from_str(option).unwrap())
+        .collect::<Vec<GameOption>>();
+    let config = Connect4Config::from_options(&options).unwrap();
+    assert_eq!((config.rows, config.columns, config.win_length), (4, 5, 5));
+}
@@ -0,0 +1,78 @@
+use duel_game::{ConfigOption, GameConfig, GameOption, OptionKind, OptionValue};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+#[derive(Serialize, Deserialize)]
+pub struct MnkConfig {
+    pub width: usize,
+    pub height: usize,
+    pub k: usize,
+}
+
+impl MnkConfig {
+    const WIDTH_NAME: &'static str = "width";
+    const HEIGHT_NAME: &'static str = "height";
+    const K_NAME: &'static str = "k";
+}
+
+impl GameConfig for MnkConfig {
+    fn options() -> Vec<ConfigOption> {
+        vec![
+            ConfigOption::new(
+                MnkConfig::WIDTH_NAME,
+                "Width of the grid (3-15)",
+                OptionKind::Integer,
+            ),
+            ConfigOption::new(
+                MnkConfig::HEIGHT_NAME,
+                "Height of the grid (3-15)",
+                OptionKind::Integer,
+            ),
+            ConfigOption::new(
+                MnkConfig::K_NAME,
+                "Number of aligned marks to win (3-10, at most the longest side)",
+                OptionKind::Integer,
+            ),
+        ]
+    }
+
+    fn from_options(options: &[GameOption]) -> anyhow::Result<Self> {
+        let mut width = 3;
+        let mut height = 3;
+        let mut k = 3;
+        for option in options.iter() {
+            match option.name.as_str() {
+                MnkConfig::WIDTH_NAME => {
+                    if let OptionValue::Integer(width_desired) = option.value {
+                        width = width_desired.clamp(3, 15) as usize;
+                    }
+                }
+                MnkConfig::HEIGHT_NAME => {
+                    if let OptionValue::Integer(height_desired) = option.value {
+                        height = height_desired.clamp(3, 15) as usize;
+                    }
+                }
+                MnkConfig::K_NAME => {
+                    if let OptionValue::Integer(k_desired) = option.value {
+                        k = k_desired.clamp(3, 10) as usize;
+                    }
+                }
+                _ => (),
+            }
+        }
+        // A line longer than the grid could never be completed
+        let k = k.min(width.max(height));
+
+        Ok(Self { width, height, k })
+    }
+}
+
+impl Display for MnkConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Width: {} | Height: {} | K: {}",
+            self.width, self.height, self.k
+        )
+    }
+}
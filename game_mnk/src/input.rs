@@ -0,0 +1,28 @@
+use anyhow::Error;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct PlayerInput {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl FromStr for PlayerInput {
+    type Err = Error;
+
+    // Should be in the form of "(5,6)"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || Error::msg(format!("Error parsing output: {}", s));
+        let (x_str, y_str) = s
+            .trim()
+            .strip_prefix('(')
+            .and_then(|s| s.strip_suffix(')'))
+            .and_then(|s| s.split_once(','))
+            .ok_or_else(error)?;
+        Ok(Self {
+            x: x_str.trim().parse().map_err(|_| error())?,
+            y: y_str.trim().parse().map_err(|_| error())?,
+        })
+    }
+}
@@ -0,0 +1,168 @@
+pub mod config;
+pub mod input;
+#[cfg(test)]
+mod tests;
+
+use crate::config::MnkConfig;
+use crate::input::PlayerInput;
+use duel_game::{DuelGame, GameOutcome, PlayerTurn};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub enum GameCell {
+    Empty,
+    Mark1,
+    Mark2,
+}
+
+#[derive(Debug)]
+pub enum GameError {
+    InputOutOfBounds,
+    CellTaken,
+}
+
+impl std::error::Error for GameError {}
+
+impl Display for GameError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameError::InputOutOfBounds => write!(f, "Input is out of bounds"),
+            GameError::CellTaken => write!(f, "Cell is already taken"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct MnkGame {
+    grid: Vec<Vec<GameCell>>,
+    k: usize,
+}
+
+impl DuelGame for MnkGame {
+    type Config = MnkConfig;
+    type Input = PlayerInput;
+    type GameError = GameError;
+
+    fn new(config: Self::Config) -> Self {
+        Self {
+            grid: (0..config.width)
+                .map(|_| vec![GameCell::Empty; config.height])
+                .collect(),
+            k: config.k,
+        }
+    }
+
+    fn to_console_player1(&self) -> String {
+        self.grid
+            .iter()
+            .map(|column| {
+                column
+                    .iter()
+                    .map(|cell| match cell {
+                        GameCell::Empty => "_".to_string(),
+                        GameCell::Mark1 => "*".to_string(),
+                        GameCell::Mark2 => "+".to_string(),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn to_console_player2(&self) -> String {
+        self.grid
+            .iter()
+            .map(|column| {
+                column
+                    .iter()
+                    .map(|cell| match cell {
+                        GameCell::Empty => "_".to_string(),
+                        GameCell::Mark1 => "+".to_string(),
+                        GameCell::Mark2 => "*".to_string(),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn to_discord(&self) -> String {
+        self.grid
+            .iter()
+            .map(|column| {
+                column
+                    .iter()
+                    .map(|cell| match cell {
+                        GameCell::Empty => "⬛".to_string(),
+                        GameCell::Mark1 => "❌".to_string(),
+                        GameCell::Mark2 => "⭕".to_string(),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn play(
+        &mut self,
+        player_input: Self::Input,
+        n: PlayerTurn,
+    ) -> Result<GameOutcome, Self::GameError> {
+        let cell = self
+            .grid
+            .get_mut(player_input.x)
+            .and_then(|column| column.get_mut(player_input.y))
+            .ok_or(GameError::InputOutOfBounds)?;
+        if *cell != GameCell::Empty {
+            return Err(GameError::CellTaken);
+        }
+        *cell = mark_cell(n);
+
+        if self.is_winning(player_input.x, player_input.y) {
+            Ok(GameOutcome::Win(n))
+        } else if self.is_full() {
+            Ok(GameOutcome::Draw)
+        } else {
+            Ok(GameOutcome::Ongoing)
+        }
+    }
+}
+
+impl MnkGame {
+    fn get_cell(&self, x: isize, y: isize) -> Option<GameCell> {
+        let column = self.grid.get(usize::try_from(x).ok()?)?;
+        column.get(usize::try_from(y).ok()?).copied()
+    }
+
+    fn is_full(&self) -> bool {
+        self.grid
+            .iter()
+            .flatten()
+            .all(|cell| *cell != GameCell::Empty)
+    }
+
+    // Check the lines going through the last mark played
+    fn is_winning(&self, x: usize, y: usize) -> bool {
+        let mark = self.grid[x][y];
+        let (x, y) = (x as isize, y as isize);
+        let count = |dx: isize, dy: isize| {
+            (1..)
+                .take_while(|&i| self.get_cell(x + i * dx, y + i * dy) == Some(mark))
+                .count()
+        };
+        [(0, 1), (1, 0), (1, 1), (1, -1)]
+            .iter()
+            .any(|&(dx, dy)| 1 + count(dx, dy) + count(-dx, -dy) >= self.k)
+    }
+}
+
+fn mark_cell(n: PlayerTurn) -> GameCell {
+    match n {
+        PlayerTurn::Player1 => GameCell::Mark1,
+        PlayerTurn::Player2 => GameCell::Mark2,
+    }
+}
@@ -0,0 +1,95 @@
+use crate::config::MnkConfig;
+use crate::input::PlayerInput;
+use crate::{GameError, MnkGame};
+use duel_game::{DuelGame, GameConfig, GameOption, GameOutcome, PlayerTurn};
+use std::str::FromStr;
+
+fn game(width: usize, height: usize, k: usize) -> MnkGame {
+    MnkGame::new(MnkConfig { width, height, k })
+}
+
+// Play the positions alternately from Player1 and return the last outcome
+fn play_all(game: &mut MnkGame, positions: &[(usize, usize)]) -> GameOutcome {
+    let mut n = PlayerTurn::Player1;
+    let mut outcome = GameOutcome::Ongoing;
+    for &(x, y) in positions {
+        assert_eq!(outcome, GameOutcome::Ongoing);
+        outcome = game.play(PlayerInput { x, y }, n).unwrap();
+        n = n.next();
+    }
+    outcome
+}
+
+#[test]
+fn tic_tac_toe_win() {
+    let mut game = game(3, 3, 3);
+    let outcome = play_all(&mut game, &[(0, 0), (1, 0), (1, 1), (2, 0), (2, 2)]);
+    assert_eq!(outcome, GameOutcome::Win(PlayerTurn::Player1));
+    assert_eq!(game.to_console_player2(), "+ _ _\n* + _\n* _ +");
+}
+
+#[test]
+fn tic_tac_toe_draw() {
+    let mut game = game(3, 3, 3);
+    let outcome = play_all(
+        &mut game,
+        &[
+            (0, 0),
+            (1, 1),
+            (2, 2),
+            (0, 1),
+            (2, 1),
+            (2, 0),
+            (0, 2),
+            (1, 2),
+            (1, 0),
+        ],
+    );
+    assert_eq!(outcome, GameOutcome::Draw);
+}
+
+#[test]
+fn larger_k_on_rectangular_board() {
+    let mut game = game(4, 6, 4);
+    let outcome = play_all(
+        &mut game,
+        &[(0, 5), (0, 0), (1, 4), (1, 0), (2, 3), (2, 0), (3, 2)],
+    );
+    assert_eq!(outcome, GameOutcome::Win(PlayerTurn::Player1));
+}
+
+#[test]
+fn invalid_inputs() {
+    let mut game = game(3, 3, 3);
+    play_all(&mut game, &[(1, 1)]);
+    let result = game.play(PlayerInput { x: 1, y: 1 }, PlayerTurn::Player2);
+    assert!(matches!(result, Err(GameError::CellTaken)));
+    let result = game.play(PlayerInput { x: 0, y: 3 }, PlayerTurn::Player2);
+    assert!(matches!(result, Err(GameError::InputOutOfBounds)));
+    assert_eq!(
+        PlayerInput::from_str(" (2, 1)\n").unwrap(),
+        PlayerInput { x: 2, y: 1 }
+    );
+    assert!(PlayerInput::from_str("2,1").is_err());
+}
+
+#[test]
+fn config_from_options() {
+    let options = ["width=20", "k = 4", "height=abc"]
+        .iter()
+        .map(|option| GameOption::from_str(option).unwrap())
+        .collect::<Vec<GameOption>>();
+    let config = MnkConfig::from_options(&options).unwrap();
+    assert_eq!((config.width, config.height, config.k), (15, 3, 4));
+    assert!(GameOption::from_str("width").is_err());
+}
+
+#[test]
+fn k_fits_the_grid() {
+    let options = ["width=3", "height=4", "k=10"]
+        .iter()
+        .map(|option| GameOption::from_str(option).unwrap())
+        .collect::<Vec<GameOption>>();
+    let config = MnkConfig::from_options(&options).unwrap();
+    assert_eq!(config.k, 4);
+}
@@ -0,0 +1,43 @@
+use duel_game::{ConfigOption, GameConfig, GameOption, OptionKind, OptionValue};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+#[derive(Serialize, Deserialize)]
+pub struct ReversiConfig {
+    pub size: usize,
+}
+
+impl ReversiConfig {
+    const SIZE_NAME: &'static str = "size";
+}
+
+impl GameConfig for ReversiConfig {
+    fn options() -> Vec<ConfigOption> {
+        vec![ConfigOption::new(
+            ReversiConfig::SIZE_NAME,
+            "Size of the grid, rounded down to an even number (4-12)",
+            OptionKind::Integer,
+        )]
+    }
+
+    fn from_options(options: &[GameOption]) -> anyhow::Result<Self> {
+        let size = options
+            .iter()
+            .find_map(|option| match option.value {
+                OptionValue::Integer(size_desired) if option.name == ReversiConfig::SIZE_NAME => {
+                    // The four starting discs need an even size to be centered
+                    Some(size_desired.clamp(4, 12) as usize / 2 * 2)
+                }
+                _ => None,
+            })
+            .unwrap_or(8);
+
+        Ok(Self { size })
+    }
+}
+
+impl Display for ReversiConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Size: {}", self.size)
+    }
+}
@@ -0,0 +1,31 @@
+use anyhow::Error;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub enum PlayerInput {
+    Place { x: usize, y: usize },
+    Pass,
+}
+
+impl FromStr for PlayerInput {
+    type Err = Error;
+
+    // Should be in the form of "(5,6)" or "pass"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s == "pass" {
+            return Ok(Self::Pass);
+        }
+        let error = || Error::msg(format!("Error parsing output: {}", s));
+        let (x_str, y_str) = s
+            .strip_prefix('(')
+            .and_then(|s| s.strip_suffix(')'))
+            .and_then(|s| s.split_once(','))
+            .ok_or_else(error)?;
+        Ok(Self::Place {
+            x: x_str.trim().parse().map_err(|_| error())?,
+            y: y_str.trim().parse().map_err(|_| error())?,
+        })
+    }
+}
@@ -0,0 +1,224 @@
+pub mod config;
+pub mod input;
+#[cfg(test)]
+mod tests;
+
+use crate::config::ReversiConfig;
+use crate::input::PlayerInput;
+use duel_game::{DuelGame, GameOutcome, PlayerTurn, Score};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub enum GameCell {
+    Empty,
+    Disc1,
+    Disc2,
+}
+
+#[derive(Debug)]
+pub enum GameError {
+    InputOutOfBounds,
+    CellTaken,
+    NoFlip,
+    InvalidPass,
+}
+
+impl std::error::Error for GameError {}
+
+impl Display for GameError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameError::InputOutOfBounds => write!(f, "Input is out of bounds"),
+            GameError::CellTaken => write!(f, "Cell is already taken"),
+            GameError::NoFlip => write!(f, "Disc does not flip any opponent disc"),
+            GameError::InvalidPass => write!(f, "Cannot pass while a disc can be placed"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ReversiGame(Vec<Vec<GameCell>>);
+
+impl DuelGame for ReversiGame {
+    type Config = ReversiConfig;
+    type Input = PlayerInput;
+    type GameError = GameError;
+
+    fn new(config: Self::Config) -> Self {
+        let mut grid = vec![vec![GameCell::Empty; config.size]; config.size];
+        let middle = config.size / 2;
+        grid[middle - 1][middle - 1] = GameCell::Disc2;
+        grid[middle][middle] = GameCell::Disc2;
+        grid[middle - 1][middle] = GameCell::Disc1;
+        grid[middle][middle - 1] = GameCell::Disc1;
+        Self(grid)
+    }
+
+    fn to_console_player1(&self) -> String {
+        self.0
+            .iter()
+            .map(|column| {
+                column
+                    .iter()
+                    .map(|cell| match cell {
+                        GameCell::Empty => "_".to_string(),
+                        GameCell::Disc1 => "*".to_string(),
+                        GameCell::Disc2 => "+".to_string(),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn to_console_player2(&self) -> String {
+        self.0
+            .iter()
+            .map(|column| {
+                column
+                    .iter()
+                    .map(|cell| match cell {
+                        GameCell::Empty => "_".to_string(),
+                        GameCell::Disc1 => "+".to_string(),
+                        GameCell::Disc2 => "*".to_string(),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn to_discord(&self) -> String {
+        self.0
+            .iter()
+            .map(|column| {
+                column
+                    .iter()
+                    .map(|cell| match cell {
+                        GameCell::Empty => "🟩".to_string(),
+                        GameCell::Disc1 => "⚫".to_string(),
+                        GameCell::Disc2 => "⚪".to_string(),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn play(
+        &mut self,
+        player_input: Self::Input,
+        n: PlayerTurn,
+    ) -> Result<GameOutcome, Self::GameError> {
+        match player_input {
+            PlayerInput::Pass => {
+                if self.can_place(n) {
+                    return Err(GameError::InvalidPass);
+                }
+            }
+            PlayerInput::Place { x, y } => {
+                let cell = self.get_cell(x as isize, y as isize);
+                match cell {
+                    None => return Err(GameError::InputOutOfBounds),
+                    Some(GameCell::Empty) => (),
+                    Some(_) => return Err(GameError::CellTaken),
+                }
+
+                let flips = self.flips(x, y, n);
+                if flips.is_empty() {
+                    return Err(GameError::NoFlip);
+                }
+                self.0[x][y] = disc_cell(n);
+                for (flip_x, flip_y) in flips {
+                    self.0[flip_x][flip_y] = disc_cell(n);
+                }
+            }
+        }
+
+        // The game ends when nobody can place a disc, the other player passes otherwise
+        if self.can_place(n) || self.can_place(n.next()) {
+            return Ok(GameOutcome::Ongoing);
+        }
+        let score = self.disc_count();
+        match score.player1.cmp(&score.player2) {
+            Ordering::Greater => Ok(GameOutcome::Win(PlayerTurn::Player1)),
+            Ordering::Less => Ok(GameOutcome::Win(PlayerTurn::Player2)),
+            Ordering::Equal => Ok(GameOutcome::Draw),
+        }
+    }
+
+    fn score(&self) -> Option<Score> {
+        Some(self.disc_count())
+    }
+}
+
+impl ReversiGame {
+    fn get_cell(&self, x: isize, y: isize) -> Option<GameCell> {
+        let column = self.0.get(usize::try_from(x).ok()?)?;
+        column.get(usize::try_from(y).ok()?).copied()
+    }
+
+    // Opponent discs enclosed between (x, y) and another disc of the player
+    fn flips(&self, x: usize, y: usize, n: PlayerTurn) -> Vec<(usize, usize)> {
+        let disc = disc_cell(n);
+        let opponent = disc_cell(n.next());
+        let mut flips = Vec::new();
+        for (dx, dy) in DIRECTIONS {
+            let mut line = Vec::new();
+            let (mut line_x, mut line_y) = (x as isize + dx, y as isize + dy);
+            while self.get_cell(line_x, line_y) == Some(opponent) {
+                line.push((line_x as usize, line_y as usize));
+                line_x += dx;
+                line_y += dy;
+            }
+            if self.get_cell(line_x, line_y) == Some(disc) {
+                flips.append(&mut line);
+            }
+        }
+        flips
+    }
+
+    pub fn can_place(&self, n: PlayerTurn) -> bool {
+        (0..self.0.len()).any(|x| {
+            (0..self.0[x].len())
+                .any(|y| self.0[x][y] == GameCell::Empty && !self.flips(x, y, n).is_empty())
+        })
+    }
+
+    fn disc_count(&self) -> Score {
+        let count = |disc| {
+            self.0
+                .iter()
+                .flatten()
+                .filter(|cell| **cell == disc)
+                .count()
+        };
+        Score {
+            player1: count(GameCell::Disc1),
+            player2: count(GameCell::Disc2),
+        }
+    }
+}
+
+const DIRECTIONS: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+fn disc_cell(n: PlayerTurn) -> GameCell {
+    match n {
+        PlayerTurn::Player1 => GameCell::Disc1,
+        PlayerTurn::Player2 => GameCell::Disc2,
+    }
+}
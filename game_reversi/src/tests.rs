@@ -0,0 +1,73 @@
+use crate::config::ReversiConfig;
+use crate::input::PlayerInput;
+use crate::{GameCell, GameError, ReversiGame};
+use duel_game::{DuelGame, GameOutcome, PlayerTurn, Score};
+use std::str::FromStr;
+
+#[test]
+fn opening_move_flips_a_disc() {
+    let mut game = ReversiGame::new(ReversiConfig { size: 8 });
+    let outcome = game.play(PlayerInput::Place { x: 2, y: 3 }, PlayerTurn::Player1);
+    assert!(matches!(outcome, Ok(GameOutcome::Ongoing)));
+    assert_eq!(
+        game.score(),
+        Some(Score {
+            player1: 4,
+            player2: 1
+        })
+    );
+    assert_eq!(
+        game.to_console_player2(),
+        "_ _ _ _ _ _ _ _\n\
+         _ _ _ _ _ _ _ _\n\
+         _ _ _ + _ _ _ _\n\
+         _ _ _ + + _ _ _\n\
+         _ _ _ + * _ _ _\n\
+         _ _ _ _ _ _ _ _\n\
+         _ _ _ _ _ _ _ _\n\
+         _ _ _ _ _ _ _ _"
+    );
+}
+
+#[test]
+fn invalid_placements() {
+    let mut game = ReversiGame::new(ReversiConfig { size: 8 });
+    let result = game.play(PlayerInput::Place { x: 0, y: 0 }, PlayerTurn::Player1);
+    assert!(matches!(result, Err(GameError::NoFlip)));
+    let result = game.play(PlayerInput::Place { x: 3, y: 3 }, PlayerTurn::Player1);
+    assert!(matches!(result, Err(GameError::CellTaken)));
+    let result = game.play(PlayerInput::Place { x: 8, y: 3 }, PlayerTurn::Player1);
+    assert!(matches!(result, Err(GameError::InputOutOfBounds)));
+    let result = game.play(PlayerInput::Pass, PlayerTurn::Player1);
+    assert!(matches!(result, Err(GameError::InvalidPass)));
+}
+
+#[test]
+fn forced_pass_then_final_count() {
+    let mut grid = vec![vec![GameCell::Empty; 4]; 4];
+    grid[0][0] = GameCell::Disc1;
+    grid[0][1] = GameCell::Disc2;
+    let mut game = ReversiGame(grid);
+
+    let result = game.play(PlayerInput::Pass, PlayerTurn::Player2);
+    assert!(matches!(result, Ok(GameOutcome::Ongoing)));
+    let result = game.play(PlayerInput::Place { x: 0, y: 2 }, PlayerTurn::Player1);
+    assert!(matches!(result, Ok(GameOutcome::Win(PlayerTurn::Player1))));
+    assert_eq!(
+        game.score(),
+        Some(Score {
+            player1: 3,
+            player2: 0
+        })
+    );
+}
+
+#[test]
+fn parse_inputs() {
+    assert_eq!(
+        PlayerInput::from_str("(2,3)\n").unwrap(),
+        PlayerInput::Place { x: 2, y: 3 }
+    );
+    assert_eq!(PlayerInput::from_str(" pass ").unwrap(), PlayerInput::Pass);
+    assert!(PlayerInput::from_str("(2;3)").is_err());
+}
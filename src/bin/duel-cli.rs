@@ -0,0 +1,84 @@
+use anyhow::{Error, Result};
+use duel_engine::runner::{outcome_message, play_turn};
+use duel_engine::sandbox::Sandbox;
+use duel_game::{GameOption, GameOutcome, PlayerTurn};
+use rust_discord_duel::game_registry;
+use std::path::PathBuf;
+
+const USAGE: &str = "Usage: duel-cli <game> <program1.wasm> <program2.wasm> [option=value ...]";
+
+// Play a duel between two local programs and print the board after every turn
+#[tokio::main]
+async fn main() -> Result<()> {
+    let registry = game_registry();
+    let sandbox = Sandbox::from_env()?;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.len() < 3 {
+        let games = registry
+            .iter()
+            .map(|entry| {
+                let options = entry
+                    .options()
+                    .iter()
+                    .map(|option| format!("  {}: {}", option.name, option.description))
+                    .collect::<Vec<String>>();
+                format!("{}\n{}", entry.name(), options.join("\n"))
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+        return Err(Error::msg(format!("{}\n\nGames:\n{}", USAGE, games)));
+    }
+
+    let entry = registry
+        .get(args[0].as_str())
+        .ok_or(Error::msg(format!("Unknown game: {}", args[0])))?;
+    let file_player1 = PathBuf::from(&args[1]);
+    let file_player2 = PathBuf::from(&args[2]);
+    for file_path in [&file_player1, &file_player2] {
+        if !file_path.try_exists()? {
+            return Err(Error::msg(format!(
+                "Program file {} does not exists",
+                file_path.display()
+            )));
+        }
+    }
+    let options = args[3..]
+        .iter()
+        .map(|option| option.parse().map_err(Error::msg))
+        .collect::<Result<Vec<GameOption>>>()?;
+
+    let (mut game, config) = entry.new_game(&options)?;
+    println!("{} | {}\n{}", entry.name(), config, game.to_discord());
+
+    let mut player_turn = PlayerTurn::Player1;
+    let outcome = loop {
+        let (program, file_path) = match player_turn {
+            PlayerTurn::Player1 => (1, &file_player1),
+            PlayerTurn::Player2 => (2, &file_player2),
+        };
+        let (turn, result) = play_turn(game.as_mut(), player_turn, file_path, &sandbox).await;
+        match result {
+            Ok(outcome) => {
+                println!(
+                    "\nProgram {} ({} ms, {} fuel): {}\n{}",
+                    program,
+                    turn.duration_ms,
+                    turn.fuel.unwrap_or_default(),
+                    turn.stdout.as_deref().unwrap_or_default().trim(),
+                    game.to_discord()
+                );
+                if outcome != GameOutcome::Ongoing {
+                    break outcome;
+                }
+            }
+            Err(why) => {
+                println!("\nProgram {} failed: {}", program, why);
+                break GameOutcome::Win(player_turn.next());
+            }
+        }
+        player_turn = player_turn.next();
+    };
+
+    println!("\n{}", outcome_message(outcome, game.score()));
+    Ok(())
+}
@@ -1,2 +1,3 @@
 pub mod play;
 pub mod quit;
+pub mod replay;
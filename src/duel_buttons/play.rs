@@ -1,19 +1,25 @@
+#[cfg(test)]
+mod tests;
+
 use crate::handler::{GameInstance, Handler};
+use crate::replay::{record_game, record_turn, ReplayHeader};
+use crate::store::{remove_game, save_game};
 use anyhow::{Error, Result};
-use duel_game::{DiscordDuelGame, PlayerTurn};
+use duel_engine::registry::GameRegistry;
+use duel_engine::runner::{outcome_message, play_turn};
+use duel_engine::sandbox::{LimitExceeded, Sandbox};
+use duel_game::{GameOutcome, PlayerTurn, Score};
+use serenity::http::Http;
 use serenity::model::prelude::message_component::MessageComponentInteraction;
+use serenity::model::prelude::{Message, MessageId};
 use serenity::prelude::Context;
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
 use std::ops::DerefMut;
-use std::path::{Path, PathBuf};
-use std::str::FromStr;
-use std::time::Duration;
-use tokio::time::sleep;
-use wasi_common::pipe::{ReadPipe, WritePipe};
-use wasmtime::{Engine, Linker, Module, Store};
-use wasmtime_wasi::WasiCtxBuilder;
+use tokio::sync::MutexGuard;
 
-pub async fn play_button<GAME: DiscordDuelGame>(
-    handler: &Handler<GAME>,
+pub async fn play_button(
+    handler: &Handler,
     ctx: &Context,
     command: &MessageComponentInteraction,
 ) -> Result<()> {
@@ -26,32 +32,34 @@ pub async fn play_button<GAME: DiscordDuelGame>(
         .ok_or(Error::msg(format!(
             "MessageId {} does not exists",
             message_id
-        )))?;
+        )))?
+        .clone();
+    drop(games);
     let mut game_instance = game_lock.lock().await;
-    let (end_state, discord_game_str) = play_game_instance(game_instance.deref_mut()).await?;
+    let (outcome, discord_game_str, forfeit) = play_game_instance(
+        game_instance.deref_mut(),
+        Some(message_id),
+        &handler.sandbox,
+    )
+    .await?;
 
     let mut message = channel_id.message(&ctx.http, message_id).await?;
-    if end_state {
-        let winner = match game_instance.player_turn {
-            PlayerTurn::Player1 => 1,
-            PlayerTurn::Player2 => 2,
-        };
-        drop(game_instance); // Why do I need to drop it manually ?
-        drop(games);
-        let mut games = handler.games.write().await;
-        let _ = games.remove(&(channel_id, message_id));
-        if let Some(mut info_message) = message.referenced_message {
-            let info_message_content = info_message.content.clone();
-            info_message
-                .edit(&ctx.http, |interaction| {
-                    interaction.content(format!("{}\nProgram {} WIN", info_message_content, winner))
-                })
-                .await?;
-        }
+    if outcome != GameOutcome::Ongoing {
+        finish_game(
+            handler,
+            &ctx.http,
+            &mut message,
+            game_instance,
+            outcome,
+            &discord_game_str,
+            forfeit,
+        )
+        .await?;
     } else {
+        save_game(channel_id, message_id, &game_instance).await?;
         message
             .edit(&ctx.http, |message| {
-                message.content(format!("# Game\n{}", discord_game_str))
+                message.content(format!("{}\n{}", game_instance.title(), discord_game_str))
             })
             .await?;
     }
@@ -59,85 +67,195 @@ pub async fn play_button<GAME: DiscordDuelGame>(
     Ok(())
 }
 
-pub async fn play_game_instance<GAME: DiscordDuelGame>(
-    game_instance: &mut GameInstance<GAME>,
-) -> Result<(bool, String)> {
-    let file_path = match game_instance.player_turn {
-        PlayerTurn::Player1 => game_instance.file_player1.clone(),
-        PlayerTurn::Player2 => game_instance.file_player2.clone(),
-    };
-    let input = run_file(
-        &game_instance.game,
-        file_path.as_path(),
-        &game_instance.player_turn,
-    )
-    .await?;
+// Rate a finished game and add its result to the Game Info. Within a series the next game
+// replaces it and true is returned, otherwise the game is removed
+pub async fn finish_game(
+    handler: &Handler,
+    http: &Http,
+    message: &mut Message,
+    mut game_instance: MutexGuard<'_, GameInstance>,
+    outcome: GameOutcome,
+    discord_game_str: &str,
+    forfeit: Option<Forfeit>,
+) -> Result<bool> {
+    let channel_id = message.channel_id;
+    if let Err(why) = handler
+        .ratings
+        .lock()
+        .await
+        .record(&game_instance, outcome)
+        .await
+    {
+        dbg!("Failed saving ratings: {}", why);
+    }
+    let title = game_instance.title();
+    let (mut result, series_goes_on) = end_game(&handler.registry, &mut game_instance, outcome)?;
+    if let Some(forfeit) = forfeit {
+        result = format!("{}\n{}", forfeit, result);
+    }
+    if series_goes_on {
+        record_game(message.id, &ReplayHeader::new(&game_instance)).await?;
+        save_game(channel_id, message.id, &game_instance).await?;
+        let next_game_str = game_instance.game.to_discord();
+        message
+            .edit(http, |message| {
+                message.content(format!("{}\n{}", game_instance.title(), next_game_str))
+            })
+            .await?;
+    } else {
+        drop(game_instance);
+        let mut games = handler.games.write().await;
+        let _ = games.remove(&(channel_id, message.id));
+        drop(games);
+        remove_game(channel_id, message.id).await?;
+        message
+            .edit(http, |message| {
+                message
+                    .content(format!("{}\n{}", title, discord_game_str))
+                    .components(|c| c)
+            })
+            .await?;
+    }
+    append_to_info_message(http, message, &result).await?;
+    Ok(series_goes_on)
+}
 
-    let end_state = match game_instance.game.play(input, game_instance.player_turn) {
-        Ok(state) => state,
-        Err(why) => return Err(Error::msg(format!("Error playing: {}", why))),
-    };
-    game_instance.player_turn = game_instance.player_turn.next();
+// A program whose move failed loses the game
+pub struct Forfeit {
+    // Program of the Game Info (1 or 2)
+    pub program: usize,
+    pub error: Error,
+}
 
-    Ok((end_state, game_instance.game.to_discord()))
+impl Display for Forfeit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.error.downcast_ref::<LimitExceeded>() {
+            Some(exceeded) => write!(f, "Program {} {}", self.program, exceeded),
+            None => write!(f, "Program {} failed: {}", self.program, self.error),
+        }
+    }
 }
 
-async fn run_file<GAME: DiscordDuelGame>(
-    game: &GAME,
-    file_path: &Path,
-    n: &PlayerTurn,
-) -> Result<GAME::Input> {
-    let console_str = match n {
-        PlayerTurn::Player1 => game.to_console_player1(),
-        PlayerTurn::Player2 => game.to_console_player2(),
+// Play the turn of the current player and record it in the replay of the match (if any). When
+// the program fails, the other player wins and the turn is not passed. Errors are those of the
+// bot, not of the programs
+pub async fn play_game_instance(
+    game_instance: &mut GameInstance,
+    match_id: Option<MessageId>,
+    sandbox: &Sandbox,
+) -> Result<(GameOutcome, String, Option<Forfeit>)> {
+    let player_turn = game_instance.player_turn;
+    let file_path = match player_turn {
+        PlayerTurn::Player1 => game_instance.file_player1.clone(),
+        PlayerTurn::Player2 => game_instance.file_player2.clone(),
     };
-    let file_path = file_path.to_path_buf();
-    let sleep = sleep(Duration::from_secs(3));
-    tokio::pin!(sleep);
+    let (turn, result) = play_turn(
+        game_instance.game.as_mut(),
+        player_turn,
+        &file_path,
+        sandbox,
+    )
+    .await;
+    if let Some(match_id) = match_id {
+        record_turn(match_id, &turn).await?;
+    }
 
-    tokio::select! {
-        _ = &mut sleep, if !sleep.is_elapsed() => {
-            Err(Error::msg("Program timed out (>3s)"))
+    let board = game_instance.game.to_discord();
+    match result {
+        Ok(outcome) => {
+            game_instance.player_turn = player_turn.next();
+            Ok((outcome, board, None))
         }
-        res = run_wasm(console_str, file_path) => {
-            let output_str = res?;
-            match GAME::Input::from_str(output_str.as_str()) {
-                Ok(game_input) => Ok(game_input),
-                Err(_) => Err(Error::msg(format!("Error parsing game input: {}", output_str))),
-            }
+        Err(error) => {
+            let forfeit = Forfeit {
+                program: game_instance.program_number(player_turn),
+                error,
+            };
+            Ok((GameOutcome::Win(player_turn.next()), board, Some(forfeit)))
         }
     }
 }
 
-async fn run_wasm(grid_console_string: String, file_path: PathBuf) -> Result<String> {
-    let engine = Engine::default();
-    let mut linker = Linker::new(&engine);
-    wasmtime_wasi::add_to_linker(&mut linker, |s| s)?;
-
-    let stdin = ReadPipe::from(grid_console_string);
-    let stdout = WritePipe::new_in_memory();
-
-    let wasi = WasiCtxBuilder::new()
-        .stdin(Box::new(stdin))
-        .stdout(Box::new(stdout.clone()))
-        .build();
+// Result line of a finished game for the Game Info. Within a series the next board replaces
+// the game (with the sides swapped) and true is returned until the last game
+pub fn end_game(
+    registry: &GameRegistry,
+    game_instance: &mut GameInstance,
+    outcome: GameOutcome,
+) -> Result<(String, bool)> {
+    // Seen from the programs of the Game Info
+    let program_outcome = match outcome {
+        GameOutcome::Win(n) if game_instance.program_number(n) == 2 => {
+            GameOutcome::Win(PlayerTurn::Player2)
+        }
+        GameOutcome::Win(_) => GameOutcome::Win(PlayerTurn::Player1),
+        _ => outcome,
+    };
+    let score = game_instance.game.score().map(|score| {
+        if game_instance.program_number(PlayerTurn::Player1) == 2 {
+            Score {
+                player1: score.player2,
+                player2: score.player1,
+            }
+        } else {
+            score
+        }
+    });
+    let result = outcome_message(program_outcome, score);
 
-    let mut store = Store::new(&engine, wasi);
-    let module = Module::from_file(&engine, file_path)?;
-    linker.module(&mut store, "", &module)?;
+    let Some(series) = game_instance.series.as_mut() else {
+        return Ok((result, false));
+    };
+    match program_outcome {
+        GameOutcome::Win(PlayerTurn::Player1) => series.wins1 += 1,
+        GameOutcome::Win(PlayerTurn::Player2) => series.wins2 += 1,
+        _ => series.draws += 1,
+    }
+    let result = format!(
+        "Game {} ({}): {}",
+        series.game_number, game_instance.config, result
+    );
+    if series.boards.is_empty() {
+        let series_result = match series.wins1.cmp(&series.wins2) {
+            Ordering::Greater => "Program 1 WIN",
+            Ordering::Less => "Program 2 WIN",
+            Ordering::Equal => "DRAW",
+        };
+        return Ok((
+            format!(
+                "{}\nSeries: {} ({} - {}, {} draws)",
+                result, series_result, series.wins1, series.wins2, series.draws
+            ),
+            false,
+        ));
+    }
 
-    linker
-        .get_default(&mut store, "")?
-        .typed::<(), ()>(&store)?
-        .call(&mut store, ())?;
+    let (next_game, next_config) = series.boards.remove(0);
+    series.game_number += 1;
+    let entry = registry
+        .get(game_instance.game_name)
+        .ok_or(Error::msg(format!(
+            "Unknown game: {}",
+            game_instance.game_name
+        )))?;
+    game_instance.game = entry.load_game(next_game)?;
+    game_instance.config = next_config;
+    game_instance.player_turn = PlayerTurn::Player1;
+    std::mem::swap(
+        &mut game_instance.file_player1,
+        &mut game_instance.file_player2,
+    );
+    Ok((result, true))
+}
 
-    drop(store);
-    match stdout.try_into_inner() {
-        Ok(res) => {
-            let bytes_res = res.into_inner();
-            let str_res = String::from_utf8_lossy(bytes_res.as_slice()).to_string();
-            Ok(str_res)
-        }
-        Err(_) => Err(Error::msg("Error getting stdout result")),
+pub async fn append_to_info_message(http: &Http, message: &mut Message, line: &str) -> Result<()> {
+    if let Some(info_message) = message.referenced_message.as_deref_mut() {
+        let info_message_content = info_message.content.clone();
+        info_message
+            .edit(http, |interaction| {
+                interaction.content(format!("{}\n{}", info_message_content, line))
+            })
+            .await?;
     }
+    Ok(())
 }
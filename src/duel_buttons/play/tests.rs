@@ -0,0 +1,86 @@
+use crate::duel_buttons::play::end_game;
+use crate::handler::{GameInstance, GameSeries};
+use duel_engine::registry::GameRegistry;
+use duel_game::{GameOutcome, PlayerTurn};
+use rust_discord_duel::game_registry;
+use std::path::PathBuf;
+
+fn game_instance(registry: &GameRegistry, games: usize) -> GameInstance {
+    let entry = registry.get("mnk").unwrap();
+    let (game, config) = entry.new_game(&[]).unwrap();
+    let boards = (1..games)
+        .map(|_| {
+            let (game, config) = entry.new_game(&[]).unwrap();
+            (game.save().unwrap(), config)
+        })
+        .collect();
+    GameInstance {
+        game,
+        game_name: entry.name(),
+        config,
+        player_turn: PlayerTurn::Player2,
+        file_player1: PathBuf::from("tmp/1.wasm"),
+        file_player2: PathBuf::from("tmp/2.wasm"),
+        automatic: false,
+        series: (games > 1).then_some(GameSeries {
+            game_number: 1,
+            games,
+            boards,
+            wins1: 0,
+            wins2: 0,
+            draws: 0,
+        }),
+    }
+}
+
+#[test]
+fn single_game() {
+    let registry = game_registry();
+    let mut game_instance = game_instance(&registry, 1);
+    let outcome = GameOutcome::Win(PlayerTurn::Player2);
+    let (result, series_goes_on) = end_game(&registry, &mut game_instance, outcome).unwrap();
+    assert_eq!(result, "Program 2 WIN");
+    assert!(!series_goes_on);
+}
+
+#[test]
+fn series_swaps_sides_every_game() {
+    let registry = game_registry();
+    let mut game_instance = game_instance(&registry, 3);
+    let config = game_instance.config.clone();
+
+    let outcome = GameOutcome::Win(PlayerTurn::Player1);
+    let (result, series_goes_on) = end_game(&registry, &mut game_instance, outcome).unwrap();
+    assert_eq!(result, format!("Game 1 ({}): Program 1 WIN", config));
+    assert!(series_goes_on);
+    assert_eq!(game_instance.title(), "# Game 2/3");
+    assert_eq!(game_instance.player_turn, PlayerTurn::Player1);
+    assert_eq!(game_instance.file_player1, PathBuf::from("tmp/2.wasm"));
+    assert_eq!(game_instance.program_number(PlayerTurn::Player1), 2);
+
+    // Player 1 is now the program 2 of the Game Info
+    let (result, series_goes_on) = end_game(&registry, &mut game_instance, outcome).unwrap();
+    assert_eq!(result, format!("Game 2 ({}): Program 2 WIN", config));
+    assert!(series_goes_on);
+    assert_eq!(game_instance.file_player1, PathBuf::from("tmp/1.wasm"));
+    assert_eq!(game_instance.program_number(PlayerTurn::Player1), 1);
+
+    let (result, series_goes_on) =
+        end_game(&registry, &mut game_instance, GameOutcome::Draw).unwrap();
+    assert_eq!(
+        result,
+        format!("Game 3 ({}): DRAW\nSeries: DRAW (1 - 1, 1 draws)", config)
+    );
+    assert!(!series_goes_on);
+}
+
+#[test]
+fn series_winner() {
+    let registry = game_registry();
+    let mut game_instance = game_instance(&registry, 2);
+    end_game(&registry, &mut game_instance, GameOutcome::Draw).unwrap();
+    let outcome = GameOutcome::Win(PlayerTurn::Player2);
+    let (result, series_goes_on) = end_game(&registry, &mut game_instance, outcome).unwrap();
+    assert!(result.ends_with("Program 1 WIN\nSeries: Program 1 WIN (1 - 0, 1 draws)"));
+    assert!(!series_goes_on);
+}
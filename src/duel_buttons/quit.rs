@@ -1,19 +1,27 @@
 use crate::handler::Handler;
+use crate::store::remove_game;
 use anyhow::Result;
-use duel_game::DiscordDuelGame;
 use serenity::model::prelude::message_component::MessageComponentInteraction;
 use serenity::prelude::Context;
 
-pub async fn quit_button<GAME: DiscordDuelGame>(
-    handler: &Handler<GAME>,
+pub async fn quit_button(
+    handler: &Handler,
     ctx: &Context,
     command: &MessageComponentInteraction,
 ) -> Result<()> {
     let message_id = command.message.id;
     let channel_id = command.channel_id;
 
-    let mut games = handler.games.write().await;
-    let _ = games.remove(&(channel_id, message_id));
+    let game_lock = handler
+        .games
+        .write()
+        .await
+        .remove(&(channel_id, message_id));
+    // Wait for a move being played, it saves the game before the file is removed
+    if let Some(game_lock) = game_lock {
+        drop(game_lock.lock().await);
+    }
+    remove_game(channel_id, message_id).await?;
 
     let mut message = channel_id.message(&ctx.http, message_id).await?;
     message
@@ -0,0 +1,39 @@
+use crate::duel_commands::replay::{
+    create_replay_buttons, replay_content, REPLAY_NEXT_BUTTON_PREFIX, REPLAY_PREV_BUTTON_PREFIX,
+};
+use crate::replay::{load_replay, replay_steps};
+use anyhow::{Error, Result};
+use serenity::model::prelude::message_component::MessageComponentInteraction;
+use serenity::model::prelude::InteractionResponseType;
+use serenity::prelude::Context;
+
+// The custom id holds the match id and the step to show: "<prefix><match_id>:<step>"
+pub async fn replay_button(ctx: &Context, command: &MessageComponentInteraction) -> Result<()> {
+    let custom_id = command.data.custom_id.as_str();
+    let (match_id, step) = custom_id
+        .strip_prefix(REPLAY_PREV_BUTTON_PREFIX)
+        .or(custom_id.strip_prefix(REPLAY_NEXT_BUTTON_PREFIX))
+        .and_then(|ids| ids.split_once(':'))
+        .and_then(|(match_id, step)| {
+            Some((match_id.parse::<u64>().ok()?, step.parse::<usize>().ok()?))
+        })
+        .ok_or(Error::msg(format!("Invalid replay button: {}", custom_id)))?;
+
+    let games = load_replay(match_id).await?;
+    let steps = replay_steps(&games);
+    let step = step.min(steps);
+
+    command
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::UpdateMessage)
+                .interaction_response_data(|message| {
+                    message
+                        .content(replay_content(match_id, &games, step))
+                        .components(|c| create_replay_buttons(c, match_id, step, steps))
+                })
+        })
+        .await?;
+
+    Ok(())
+}
@@ -1,5 +1,8 @@
 pub mod add;
 pub mod clear;
+pub mod leaderboard;
 pub mod list;
 pub mod remove;
+pub mod replay;
 pub mod start;
+pub mod tournament;
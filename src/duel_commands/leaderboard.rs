@@ -0,0 +1,96 @@
+use crate::handler::Handler;
+use anyhow::{Error, Result};
+use duel_engine::registry::GameRegistry;
+use serenity::builder::CreateApplicationCommand;
+use serenity::client::Context;
+use serenity::model::application::command::CommandOptionType;
+use serenity::model::prelude::application_command::{
+    ApplicationCommandInteraction, CommandDataOptionValue,
+};
+use serenity::model::prelude::InteractionResponseType;
+
+const GAME_NAME: &str = "game";
+
+pub fn create_leaderboard_command<'a>(
+    command: &'a mut CreateApplicationCommand,
+    registry: &GameRegistry,
+) -> &'a mut CreateApplicationCommand {
+    command
+        .name("leaderboard")
+        .description("Show the ratings of the programs for a game")
+        .create_option(|option| {
+            option
+                .name(GAME_NAME)
+                .description("Game of the leaderboard")
+                .required(true)
+                .kind(CommandOptionType::String);
+            for entry in registry.iter() {
+                option.add_string_choice(entry.name(), entry.name());
+            }
+            option
+        })
+}
+
+pub async fn leaderboard_command(
+    handler: &Handler,
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> Result<()> {
+    let game_name = command
+        .data
+        .options
+        .iter()
+        .find_map(|option| {
+            if option.name == GAME_NAME {
+                match option.resolved.as_ref()? {
+                    CommandDataOptionValue::String(s) => Some(s.clone()),
+                    _ => None,
+                }
+            } else {
+                None
+            }
+        })
+        .ok_or(Error::msg("Please choose a game"))?;
+
+    let ratings = handler.ratings.lock().await;
+    let rows = ratings
+        .leaderboard(game_name.as_str())
+        .iter()
+        .enumerate()
+        .map(|(rank, (program, rating))| {
+            format!(
+                "{:>2}. {:<8} {:>6.0} {:>5} {:>7.1}%",
+                rank + 1,
+                program,
+                rating.elo,
+                rating.games(),
+                rating.win_rate() * 100.0
+            )
+        })
+        .collect::<Vec<String>>();
+    drop(ratings);
+
+    let content = if rows.is_empty() {
+        format!("## Leaderboard ({})\nNo game played yet", game_name)
+    } else {
+        format!(
+            "## Leaderboard ({})\n```\n    {:<8} {:>6} {:>5} {:>8}\n{}\n```",
+            game_name,
+            "Program",
+            "Rating",
+            "Games",
+            "Win rate",
+            rows.join("\n")
+        )
+    };
+
+    command
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|message| message.content(content))
+        })
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,179 @@
+#[cfg(test)]
+mod tests;
+
+use crate::replay::{load_replay, program_name, replay_steps, ReplayGame};
+use anyhow::{Error, Result};
+use duel_game::PlayerTurn;
+use serenity::builder::{CreateApplicationCommand, CreateComponents};
+use serenity::client::Context;
+use serenity::model::application::command::CommandOptionType;
+use serenity::model::application::component::ButtonStyle;
+use serenity::model::channel::ReactionType;
+use serenity::model::prelude::application_command::{
+    ApplicationCommandInteraction, CommandDataOptionValue,
+};
+use serenity::model::prelude::InteractionResponseType;
+
+const MATCH_ID_NAME: &str = "match_id";
+const MAX_STDOUT_LENGTH: usize = 100;
+
+pub const REPLAY_PREV_BUTTON_PREFIX: &str = "replay_prev:";
+pub const REPLAY_NEXT_BUTTON_PREFIX: &str = "replay_next:";
+
+pub fn create_replay_command(
+    command: &mut CreateApplicationCommand,
+) -> &mut CreateApplicationCommand {
+    command
+        .name("replay")
+        .description("Replay a finished or running match")
+        .create_option(|option| {
+            // Match ids do not fit in a Discord integer option
+            option
+                .name(MATCH_ID_NAME)
+                .description("Match id, shown in the Game Info")
+                .required(true)
+                .kind(CommandOptionType::String)
+        })
+}
+
+pub async fn replay_command(ctx: &Context, command: &ApplicationCommandInteraction) -> Result<()> {
+    let match_id = command
+        .data
+        .options
+        .iter()
+        .find_map(|option| {
+            if option.name == MATCH_ID_NAME {
+                match option.resolved.as_ref()? {
+                    CommandDataOptionValue::String(s) => s.trim().parse::<u64>().ok(),
+                    _ => None,
+                }
+            } else {
+                None
+            }
+        })
+        .ok_or(Error::msg("Please input a valid match id"))?;
+
+    let games = load_replay(match_id).await?;
+    let steps = replay_steps(&games);
+
+    command
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|message| {
+                    message
+                        .content(replay_content(match_id, &games, 0))
+                        .components(|c| create_replay_buttons(c, match_id, 0, steps))
+                })
+        })
+        .await?;
+
+    Ok(())
+}
+
+// Step 0 is the initial board, step k is the board after the k-th turn of the match
+pub fn replay_content(match_id: u64, games: &[ReplayGame], step: usize) -> String {
+    // Game of the step and the step within that game
+    let mut game_index = 0;
+    let mut game_step = step;
+    while game_index + 1 < games.len() && game_step > games[game_index].turns.len() {
+        game_step -= games[game_index].turns.len();
+        game_index += 1;
+    }
+    let ReplayGame { header, turns } = &games[game_index];
+    let step_game = game_step.min(turns.len());
+
+    let mut content = format!(
+        "> # Replay `{}`\n> {} **VS** {}\n> Game: {}\n> Options: {}\n> Step: {}/{}",
+        match_id,
+        program_name(&header.file_player1),
+        program_name(&header.file_player2),
+        header.game_name,
+        header.config,
+        step,
+        replay_steps(games)
+    );
+    if games.len() > 1 {
+        content.push_str(&format!(
+            " | Series game: {}/{}",
+            game_index + 1,
+            games.len()
+        ));
+    }
+
+    // A failed turn does not change the board
+    let board = turns[..step_game]
+        .iter()
+        .rev()
+        .find_map(|turn| turn.board.as_ref())
+        .unwrap_or(&header.board);
+
+    if let Some(turn) = step_game.checked_sub(1).map(|index| &turns[index]) {
+        // The sides of the header are those of this game
+        let program = match turn.player_turn {
+            PlayerTurn::Player1 => program_name(&header.file_player1),
+            PlayerTurn::Player2 => program_name(&header.file_player2),
+        };
+        let stdout = match &turn.stdout {
+            Some(stdout) if stdout.chars().count() > MAX_STDOUT_LENGTH => {
+                format!(
+                    "{}...",
+                    stdout.chars().take(MAX_STDOUT_LENGTH).collect::<String>()
+                )
+            }
+            Some(stdout) => stdout.clone(),
+            None => "none".to_string(),
+        };
+        let cost = match turn.fuel {
+            Some(fuel) => format!("{}ms, {} fuel", turn.duration_ms, fuel),
+            None => format!("{}ms", turn.duration_ms),
+        };
+        content.push_str(&format!(
+            "\n> Program {} ({}): `{}`",
+            program,
+            cost,
+            stdout.trim()
+        ));
+        if let Some(error) = &turn.error {
+            content.push_str(&format!("\n> Error: {}", error));
+        }
+    }
+
+    format!("{}\n{}", content, board)
+}
+
+pub fn create_replay_buttons(
+    components: &mut CreateComponents,
+    match_id: u64,
+    step: usize,
+    steps: usize,
+) -> &mut CreateComponents {
+    components.create_action_row(|row| {
+        row.create_button(|button| {
+            button
+                .custom_id(format!(
+                    "{}{}:{}",
+                    REPLAY_PREV_BUTTON_PREFIX,
+                    match_id,
+                    step.saturating_sub(1)
+                ))
+                .label("Prev")
+                .emoji(ReactionType::Unicode("⏪".to_string()))
+                .style(ButtonStyle::Secondary)
+                .disabled(step == 0)
+        })
+        .create_button(|button| {
+            button
+                .custom_id(format!(
+                    "{}{}:{}",
+                    REPLAY_NEXT_BUTTON_PREFIX,
+                    match_id,
+                    step + 1
+                ))
+                .label("Next")
+                .emoji(ReactionType::Unicode("⏩".to_string()))
+                .style(ButtonStyle::Secondary)
+                .disabled(step >= steps)
+        })
+    })
+}
@@ -0,0 +1,60 @@
+use crate::duel_commands::replay::replay_content;
+use crate::replay::{ReplayGame, ReplayHeader};
+use duel_engine::runner::ReplayTurn;
+use duel_game::PlayerTurn;
+use std::path::PathBuf;
+
+fn replay_game(file_player1: &str, file_player2: &str, boards: &[&str]) -> ReplayGame {
+    let turns = boards
+        .iter()
+        .enumerate()
+        .map(|(index, board)| ReplayTurn {
+            player_turn: if index % 2 == 0 {
+                PlayerTurn::Player1
+            } else {
+                PlayerTurn::Player2
+            },
+            stdin: String::new(),
+            stdout: Some(board.to_string()),
+            input: None,
+            duration_ms: 1,
+            fuel: None,
+            board: Some(board.to_string()),
+            error: None,
+        })
+        .collect();
+    ReplayGame {
+        header: ReplayHeader {
+            game_name: "mnk".to_string(),
+            config: "Width: 3 | Height: 3 | K: 3".to_string(),
+            file_player1: PathBuf::from(file_player1),
+            file_player2: PathBuf::from(file_player2),
+            board: format!("start {}", file_player1),
+        },
+        turns,
+    }
+}
+
+#[test]
+fn series_steps_follow_the_games() {
+    let games = [
+        replay_game("tmp/1.wasm", "tmp/2.wasm", &["a", "b"]),
+        replay_game("tmp/2.wasm", "tmp/1.wasm", &["c"]),
+    ];
+
+    let content = replay_content(7, &games, 0);
+    assert!(content.contains("> 1 **VS** 2"));
+    assert!(content.contains("Step: 0/3 | Series game: 1/2"));
+    assert!(content.ends_with("\nstart tmp/1.wasm"));
+
+    let content = replay_content(7, &games, 2);
+    assert!(content.contains("Program 2 (1ms): `b`"));
+    assert!(content.ends_with("\nb"));
+
+    // The first turn of the second game is played by program 2 as player 1
+    let content = replay_content(7, &games, 3);
+    assert!(content.contains("> 2 **VS** 1"));
+    assert!(content.contains("Step: 3/3 | Series game: 2/2"));
+    assert!(content.contains("Program 2 (1ms): `c`"));
+    assert!(content.ends_with("\nc"));
+}
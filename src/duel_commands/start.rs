@@ -1,9 +1,13 @@
-use crate::duel_buttons::play::play_game_instance;
-use crate::handler::{GameInstance, Handler};
+use crate::duel_buttons::play::{finish_game, play_game_instance};
+use crate::handler::{GameInstance, GameSeries, Handler};
+use crate::options::{create_config_options, game_options};
+use crate::replay::{start_replay, ReplayHeader};
+use crate::store::save_game;
 use anyhow::Error;
 use anyhow::Result;
-use duel_game::{DiscordConfig, DiscordDuelGame, PlayerTurn};
-use serenity::builder::CreateApplicationCommand;
+use duel_engine::registry::GameRegistry;
+use duel_game::{GameOption, GameOutcome, OptionValue, PlayerTurn, SEED_OPTION};
+use serenity::builder::{CreateApplicationCommand, CreateComponents};
 use serenity::client::Context;
 use serenity::http::Http;
 use serenity::model::application::command::CommandOptionType;
@@ -15,52 +19,80 @@ use serenity::model::prelude::application_command::{
 use serenity::model::prelude::{ChannelId, InteractionResponseType, Message};
 use std::ops::DerefMut;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Mutex;
 
 const PROGRAM1_NAME: &str = "program1";
 const PROGRAM2_NAME: &str = "program2";
 const AUTOMATIC_NAME: &str = "automatic";
+const SERIES_NAME: &str = "series";
 
 pub const PLAY_BUTTON_ID: &str = "play_button_id";
 pub const QUIT_BUTTON_ID: &str = "quit_button_id";
 
-pub fn create_start_command<GAME: DiscordDuelGame>(
-    command: &mut CreateApplicationCommand,
-) -> &mut CreateApplicationCommand {
-    let command = command
+pub fn create_start_command<'a>(
+    command: &'a mut CreateApplicationCommand,
+    registry: &GameRegistry,
+) -> &'a mut CreateApplicationCommand {
+    command
         .name("start")
-        .description("Play a Duel Game with a .wasm program")
-        .create_option(|option| {
-            option
-                .name(PROGRAM1_NAME)
-                .description("Program Id 1")
-                .required(true)
-                .kind(CommandOptionType::Integer)
-        })
-        .create_option(|option| {
-            option
-                .name(PROGRAM2_NAME)
-                .description("Program Id 2")
-                .required(true)
-                .kind(CommandOptionType::Integer)
-        })
-        .create_option(|option| {
-            option
-                .name(AUTOMATIC_NAME)
-                .description("Play automatically")
-                .required(false)
-                .kind(CommandOptionType::Boolean)
+        .description("Play a Duel Game with a .wasm program");
+    for entry in registry.iter() {
+        command.create_option(|subcommand| {
+            subcommand
+                .name(entry.name())
+                .description(entry.description())
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|option| {
+                    option
+                        .name(PROGRAM1_NAME)
+                        .description("Program Id 1")
+                        .required(true)
+                        .kind(CommandOptionType::Integer)
+                })
+                .create_sub_option(|option| {
+                    option
+                        .name(PROGRAM2_NAME)
+                        .description("Program Id 2")
+                        .required(true)
+                        .kind(CommandOptionType::Integer)
+                })
+                .create_sub_option(|option| {
+                    option
+                        .name(AUTOMATIC_NAME)
+                        .description("Play automatically")
+                        .required(false)
+                        .kind(CommandOptionType::Boolean)
+                })
+                .create_sub_option(|option| {
+                    option
+                        .name(SERIES_NAME)
+                        .description("Number of games, sides are swapped every game (1-9)")
+                        .required(false)
+                        .kind(CommandOptionType::Integer)
+                });
+            create_config_options(subcommand, entry.options())
         });
-    GAME::Config::create_command(command)
+    }
+    command
 }
 
-pub async fn start_command<GAME: DiscordDuelGame>(
-    handler: &Handler<GAME>,
+pub async fn start_command(
+    handler: &Handler,
     ctx: &Context,
     command: &ApplicationCommandInteraction,
 ) -> Result<()> {
-    let options = command.data.options.as_slice();
+    let subcommand = command
+        .data
+        .options
+        .first()
+        .ok_or(Error::msg("Please choose a game"))?;
+    let entry = handler
+        .registry
+        .get(subcommand.name.as_str())
+        .ok_or(Error::msg(format!("Unknown game: {}", subcommand.name)))?;
+    let options = subcommand.options.as_slice();
 
     let program1_id = options
         .iter()
@@ -94,9 +126,8 @@ pub async fn start_command<GAME: DiscordDuelGame>(
         return Err(Error::msg("Program file 2 does not exists"));
     }
 
-    let config = GAME::Config::from_options(options);
-    let displayed_config = format!("{}", config);
-    let game = GAME::new(config);
+    let config_options = game_options(options);
+    let (game, displayed_config) = entry.new_game(&config_options)?;
 
     let automatic = options
         .iter()
@@ -112,14 +143,47 @@ pub async fn start_command<GAME: DiscordDuelGame>(
         })
         .unwrap_or(false);
 
+    let series_games = options
+        .iter()
+        .find_map(|option| {
+            if option.name == SERIES_NAME {
+                Some(option.value.as_ref()?.as_i64()?.clamp(1, 9) as usize)
+            } else {
+                None
+            }
+        })
+        .unwrap_or(1);
+
+    // Every game of a series gets its own board
+    let mut boards = Vec::new();
+    for index in 1..series_games {
+        let (next_game, next_config) = entry.new_game(&series_options(&config_options, index))?;
+        boards.push((next_game.save()?, next_config));
+    }
+    let series = (series_games > 1).then_some(GameSeries {
+        game_number: 1,
+        games: series_games,
+        boards,
+        wins1: 0,
+        wins2: 0,
+        draws: 0,
+    });
+    let title = match &series {
+        Some(series) => format!("# Game 1/{}", series.games),
+        None => "# Game".to_string(),
+    };
+
     command
         .create_interaction_response(&ctx.http, |response| {
             response
                 .kind(InteractionResponseType::ChannelMessageWithSource)
                 .interaction_response_data(|message| {
                     message.content(format!(
-                        "> # Game Info\n> {} **VS** {}\n> Options: {}",
-                        program1_id, program2_id, displayed_config
+                        "> # Game Info\n> {} **VS** {}\n> Game: {}\n> Options: {}",
+                        program1_id,
+                        program2_id,
+                        entry.name(),
+                        displayed_config
                     ))
                 })
         })
@@ -128,119 +192,137 @@ pub async fn start_command<GAME: DiscordDuelGame>(
     let mut message = command
         .create_followup_message(&ctx.http, |interaction| {
             interaction
-                .content(format!("# Game\n{}", game.to_discord()))
-                .components(|c| {
-                    c.create_action_row(|row| {
-                        if !automatic {
-                            row.create_button(|button| {
-                                button
-                                    .custom_id(PLAY_BUTTON_ID)
-                                    .label("Play")
-                                    .emoji(ReactionType::Unicode("▶️".to_string()))
-                                    .style(ButtonStyle::Success)
-                            });
-                        }
-                        row.create_button(|button| {
-                            button
-                                .custom_id(QUIT_BUTTON_ID)
-                                .label("Quit")
-                                .emoji(ReactionType::Unicode("🛑".to_string()))
-                                .style(ButtonStyle::Danger)
-                        })
-                    })
-                })
+                .content(format!("{}\n{}", title, game.to_discord()))
+                .components(|c| create_game_buttons(c, automatic))
         })
         .await?;
 
+    let game_instance = GameInstance {
+        game,
+        game_name: entry.name(),
+        config: displayed_config,
+        player_turn: PlayerTurn::Player1,
+        file_player1: file_path1,
+        file_player2: file_path2,
+        automatic,
+        series,
+    };
+    // The game message id identifies the match in its replay
+    start_replay(message.id, &ReplayHeader::new(&game_instance)).await?;
+    let match_id = message.id;
+    if let Some(info_message) = message.referenced_message.as_deref_mut() {
+        let info_message_content = info_message.content.clone();
+        info_message
+            .edit(&ctx.http, |interaction| {
+                interaction.content(format!("{}\n> Match: `{}`", info_message_content, match_id))
+            })
+            .await?;
+    }
+
+    save_game(message.channel_id, message.id, &game_instance).await?;
     let mut games = handler.games.write().await;
     games.insert(
         (message.channel_id, message.id),
-        Mutex::new(GameInstance {
-            game,
-            player_turn: PlayerTurn::Player1,
-            file_player1: file_path1,
-            file_player2: file_path2,
-        }),
+        Arc::new(Mutex::new(game_instance)),
     );
     drop(games);
 
     if automatic {
-        let channel_id = command.channel_id;
-        match loop_game(&ctx.http, handler, channel_id, &mut message).await {
-            Ok(_) => (),
-            Err(why) => {
-                let games = handler.games.read().await;
-                if let Some(game_lock) = games.get(&(channel_id, message.id)) {
-                    let game_instance = game_lock.lock().await;
-                    let winner = match game_instance.player_turn {
-                        PlayerTurn::Player1 => 1,
-                        PlayerTurn::Player2 => 2,
-                    };
-                    if let Some(info_message) = message.referenced_message.as_deref_mut() {
-                        let info_message_content = info_message.content.clone();
-                        info_message
-                            .edit(&ctx.http, |interaction| {
-                                interaction.content(format!(
-                                    "{}\nProgram {} WIN because of error",
-                                    info_message_content, winner
-                                ))
-                            })
-                            .await?;
-                    }
-                }
-                return Err(why);
-            }
-        }
+        loop_game(&ctx.http, handler, command.channel_id, &mut message).await?;
     }
 
     Ok(())
 }
 
-async fn loop_game<GAME: DiscordDuelGame>(
+// A given seed is shifted by the index of the game, otherwise every game would get the same board
+fn series_options(options: &[GameOption], index: usize) -> Vec<GameOption> {
+    options
+        .iter()
+        .map(|option| match option.value {
+            OptionValue::Integer(seed) if option.name == SEED_OPTION => GameOption {
+                name: option.name.clone(),
+                value: OptionValue::Integer(seed.wrapping_add(index as i64)),
+            },
+            _ => option.clone(),
+        })
+        .collect()
+}
+
+pub fn create_game_buttons(
+    components: &mut CreateComponents,
+    automatic: bool,
+) -> &mut CreateComponents {
+    components.create_action_row(|row| {
+        if !automatic {
+            row.create_button(|button| {
+                button
+                    .custom_id(PLAY_BUTTON_ID)
+                    .label("Play")
+                    .emoji(ReactionType::Unicode("▶️".to_string()))
+                    .style(ButtonStyle::Success)
+            });
+        }
+        row.create_button(|button| {
+            button
+                .custom_id(QUIT_BUTTON_ID)
+                .label("Quit")
+                .emoji(ReactionType::Unicode("🛑".to_string()))
+                .style(ButtonStyle::Danger)
+        })
+    })
+}
+
+async fn loop_game(
     http: &Http,
-    handler: &Handler<GAME>,
+    handler: &Handler,
     channel_id: ChannelId,
     message: &mut Message,
 ) -> Result<()> {
     loop {
-        let games = handler.games.read().await;
-        match games.get(&(channel_id, message.id)) {
+        let game_lock = handler
+            .games
+            .read()
+            .await
+            .get(&(channel_id, message.id))
+            .cloned();
+        match game_lock {
             None => break,
             Some(game_lock) => {
                 let mut game_instance = game_lock.lock().await;
-                let (end_state, discord_game_str) =
-                    play_game_instance(game_instance.deref_mut()).await?;
-                if end_state {
-                    let winner = match game_instance.player_turn {
-                        PlayerTurn::Player1 => 1,
-                        PlayerTurn::Player2 => 2,
-                    };
-                    drop(game_instance); // Why do I need to drop it manually ?
-                    drop(games);
-                    let mut games = handler.games.write().await;
-                    let _ = games.remove(&(channel_id, message.id));
-                    if let Some(info_message) = message.referenced_message.as_deref_mut() {
-                        let info_message_content = info_message.content.clone();
-                        info_message
-                            .edit(http, |interaction| {
-                                interaction.content(format!(
-                                    "{}\nProgram {} WIN",
-                                    info_message_content, winner
-                                ))
-                            })
-                            .await?;
+                let (outcome, discord_game_str, forfeit) = play_game_instance(
+                    game_instance.deref_mut(),
+                    Some(message.id),
+                    &handler.sandbox,
+                )
+                .await?;
+                if outcome != GameOutcome::Ongoing {
+                    let series_goes_on = finish_game(
+                        handler,
+                        http,
+                        message,
+                        game_instance,
+                        outcome,
+                        &discord_game_str,
+                        forfeit,
+                    )
+                    .await?;
+                    if !series_goes_on {
+                        break;
                     }
-                    break;
                 } else {
+                    save_game(channel_id, message.id, &game_instance).await?;
                     message
                         .edit(http, |interaction| {
-                            interaction.content(format!("# Game\n{}", discord_game_str))
+                            interaction.content(format!(
+                                "{}\n{}",
+                                game_instance.title(),
+                                discord_game_str
+                            ))
                         })
                         .await?;
                 }
             }
         }
-        drop(games);
         tokio::time::sleep(Duration::from_millis(300)).await;
     }
 
@@ -0,0 +1,471 @@
+use crate::duel_buttons::play::play_game_instance;
+use crate::handler::{GameInstance, Handler};
+use crate::options::{create_config_options, game_options};
+use crate::tournament::{Series, Standing, Tournament, TournamentFormat};
+use anyhow::{Error, Result};
+use duel_engine::registry::{AnyGame, GameEntry, GameRegistry};
+use duel_engine::sandbox::ProgramTimeout;
+use duel_game::{GameOption, GameOutcome, PlayerTurn};
+use serenity::builder::CreateApplicationCommand;
+use serenity::client::Context;
+use serenity::model::application::command::CommandOptionType;
+use serenity::model::prelude::application_command::{
+    ApplicationCommandInteraction, CommandDataOption, CommandDataOptionValue,
+};
+use serenity::model::prelude::{InteractionResponseType, Message};
+use std::path::PathBuf;
+
+const PROGRAMS_NAME: &str = "programs";
+const FORMAT_NAME: &str = "format";
+const BEST_OF_NAME: &str = "best_of";
+const ROUNDS_NAME: &str = "rounds";
+// Even so that every pairing plays both sides as often
+const BEST_OF_CHOICES: [i64; 5] = [2, 4, 6, 8, 10];
+
+pub fn create_tournament_command<'a>(
+    command: &'a mut CreateApplicationCommand,
+    registry: &GameRegistry,
+) -> &'a mut CreateApplicationCommand {
+    command
+        .name("tournament")
+        .description("Play a tournament between programs, a restart of the bot ends it");
+    for entry in registry.iter() {
+        command.create_option(|subcommand| {
+            subcommand
+                .name(entry.name())
+                .description(entry.description())
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|option| {
+                    option
+                        .name(PROGRAMS_NAME)
+                        .description("Program ids separated by spaces, or \"all\"")
+                        .required(true)
+                        .kind(CommandOptionType::String)
+                })
+                .create_sub_option(|option| {
+                    option
+                        .name(FORMAT_NAME)
+                        .description("Format of the tournament (round_robin by default)")
+                        .required(false)
+                        .kind(CommandOptionType::String);
+                    for format in TournamentFormat::ALL {
+                        option.add_string_choice(format.name(), format.name());
+                    }
+                    option
+                })
+                .create_sub_option(|option| {
+                    option
+                        .name(BEST_OF_NAME)
+                        .description(
+                            "Games per pairing, sides are swapped every game (2 by default)",
+                        )
+                        .required(false)
+                        .kind(CommandOptionType::Integer);
+                    for best_of in BEST_OF_CHOICES {
+                        option.add_int_choice(best_of, best_of as i32);
+                    }
+                    option
+                })
+                .create_sub_option(|option| {
+                    option
+                        .name(ROUNDS_NAME)
+                        .description("Rounds of a swiss tournament (1-10)")
+                        .required(false)
+                        .kind(CommandOptionType::Integer)
+                        .min_int_value(1)
+                        .max_int_value(10)
+                });
+            create_config_options(subcommand, entry.options())
+        });
+    }
+    command
+}
+
+pub async fn tournament_command(
+    handler: &Handler,
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> Result<()> {
+    let subcommand = command
+        .data
+        .options
+        .first()
+        .ok_or(Error::msg("Please choose a game"))?;
+    let entry = handler
+        .registry
+        .get(subcommand.name.as_str())
+        .ok_or(Error::msg(format!("Unknown game: {}", subcommand.name)))?;
+    let options = subcommand.options.as_slice();
+
+    let programs = options
+        .iter()
+        .find_map(|option| {
+            if option.name == PROGRAMS_NAME {
+                match option.resolved.as_ref()? {
+                    CommandDataOptionValue::String(s) => Some(s.clone()),
+                    _ => None,
+                }
+            } else {
+                None
+            }
+        })
+        .ok_or(Error::msg("Please input the program ids"))?;
+    let mut program_ids = parse_program_ids(programs.as_str()).await?;
+
+    let format = options
+        .iter()
+        .find_map(|option| {
+            if option.name == FORMAT_NAME {
+                match option.resolved.as_ref()? {
+                    CommandDataOptionValue::String(s) => TournamentFormat::from_name(s),
+                    _ => None,
+                }
+            } else {
+                None
+            }
+        })
+        .unwrap_or(TournamentFormat::RoundRobin);
+    let best_of = integer_option(options, BEST_OF_NAME).unwrap_or(2);
+    if !BEST_OF_CHOICES.contains(&best_of) {
+        return Err(Error::msg("Games per pairing must be 2, 4, 6, 8 or 10"));
+    }
+    let swiss_rounds = match integer_option(options, ROUNDS_NAME) {
+        None => None,
+        Some(rounds @ 1..=10) => Some(rounds as usize),
+        Some(_) => return Err(Error::msg("Rounds must be between 1 and 10")),
+    };
+    let config_options = game_options(options);
+
+    // The best rated programs are the best seeds
+    let ratings = handler.ratings.lock().await;
+    program_ids.sort_by(|a, b| {
+        let elo = |program_id: &usize| ratings.elo(entry.name(), &program_id.to_string());
+        elo(b).total_cmp(&elo(a))
+    });
+    drop(ratings);
+
+    let mut tournament = Tournament::new(
+        entry.name(),
+        format,
+        best_of as usize,
+        swiss_rounds,
+        program_ids,
+    );
+    command
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|message| {
+                    message.content(progress_content(&tournament, 0))
+                })
+        })
+        .await?;
+    let mut message = command.get_interaction_response(&ctx.http).await?;
+    tournament.channel_id = message.channel_id.0;
+    tournament.message_id = message.id.0;
+    tournament.save().await?;
+
+    // The interaction token expires after 15 minutes, a tournament only uses its message
+    if let Err(why) = play_tournament(
+        handler,
+        ctx,
+        entry,
+        &config_options,
+        &mut tournament,
+        &mut message,
+    )
+    .await
+    {
+        let content = message.content.clone();
+        message
+            .edit(&ctx.http, |message| {
+                message.content(format!("{}\n> TOURNAMENT FAILED: {}", content, why))
+            })
+            .await?;
+        tournament.finished = true;
+        tournament.save().await?;
+    }
+
+    Ok(())
+}
+
+async fn play_tournament(
+    handler: &Handler,
+    ctx: &Context,
+    entry: &dyn GameEntry,
+    config_options: &[GameOption],
+    tournament: &mut Tournament,
+    message: &mut Message,
+) -> Result<()> {
+    let mut games_played = 0;
+    while let Some(round) = tournament.next_round() {
+        let round_len = round.len();
+        tournament.rounds.push(round);
+        for index in 0..round_len {
+            let mut series = tournament.rounds[tournament.rounds.len() - 1][index].clone();
+            if series.program2.is_some() {
+                games_played +=
+                    play_series(handler, entry, config_options, tournament, &mut series).await?;
+            }
+            tournament.finish_series(&mut series);
+            let last_round = tournament.rounds.len() - 1;
+            tournament.rounds[last_round][index] = series;
+            tournament.save().await?;
+
+            message
+                .edit(&ctx.http, |message| {
+                    message.content(progress_content(tournament, games_played))
+                })
+                .await?;
+        }
+    }
+    tournament.finished = true;
+    tournament.save().await?;
+
+    let champion = match tournament.champion() {
+        Some(champion) => format!("\n### Winner: `{}`", champion),
+        None => String::new(),
+    };
+    message
+        .channel_id
+        .send_message(&ctx.http, |message| {
+            message.content(format!(
+                "## Standings ({}){}\n{}",
+                entry.name(),
+                champion,
+                standings_table(&tournament.ranking())
+            ))
+        })
+        .await?;
+
+    Ok(())
+}
+
+fn integer_option(options: &[CommandDataOption], name: &str) -> Option<i64> {
+    options.iter().find_map(|option| {
+        if option.name == name {
+            match option.resolved.as_ref()? {
+                CommandDataOptionValue::Integer(i) => Some(*i),
+                _ => None,
+            }
+        } else {
+            None
+        }
+    })
+}
+
+// Play the games of a series, sides are swapped every game and both sides share the same board
+async fn play_series(
+    handler: &Handler,
+    entry: &dyn GameEntry,
+    options: &[GameOption],
+    tournament: &mut Tournament,
+    series: &mut Series,
+) -> Result<usize> {
+    let program1 = series.program1;
+    let program2 = series.program2.ok_or(Error::msg("A bye has no game"))?;
+    let mut board = None;
+    let mut games_played = 0;
+    for index in 0..tournament.best_of {
+        // Stop once the series is decided, only after both programs played both sides
+        let remaining = tournament.best_of - index;
+        let decided =
+            series.wins1 > series.wins2 + remaining || series.wins2 > series.wins1 + remaining;
+        if index % 2 == 0 && decided {
+            break;
+        }
+
+        let (game, config) = match board.take() {
+            Some(board) => board,
+            None => {
+                let (game, config) = entry.new_game(options)?;
+                board = Some((entry.load_game(game.save()?)?, config.clone()));
+                (game, config)
+            }
+        };
+        let (first, second) = if index % 2 == 0 {
+            (program1, program2)
+        } else {
+            (program2, program1)
+        };
+        let (outcome, failure) = play_headless(handler, entry, game, config, first, second).await?;
+        games_played += 1;
+
+        let program_id = |n: PlayerTurn| match n {
+            PlayerTurn::Player1 => first,
+            PlayerTurn::Player2 => second,
+        };
+        match outcome {
+            GameOutcome::Win(n) => {
+                tournament.standing_mut(program_id(n)).wins += 1;
+                tournament.standing_mut(program_id(n.next())).losses += 1;
+                if program_id(n) == program1 {
+                    series.wins1 += 1;
+                } else {
+                    series.wins2 += 1;
+                }
+            }
+            _ => {
+                tournament.standing_mut(program1).draws += 1;
+                tournament.standing_mut(program2).draws += 1;
+                series.draws += 1;
+            }
+        }
+        match failure {
+            Some(Failure::Timeout(n)) => tournament.standing_mut(program_id(n)).timeouts += 1,
+            Some(Failure::Error(n)) => tournament.standing_mut(program_id(n)).errors += 1,
+            None => (),
+        }
+    }
+    Ok(games_played)
+}
+
+// Series of the current round, the older rounds only show in the saved tournament
+fn progress_content(tournament: &Tournament, games_played: usize) -> String {
+    let mut content = format!(
+        "> # Tournament\n> Game: {}\n> Format: {} | Best of: {}\n> Programs: {}\n> Round: {}/{} | Games: {}",
+        tournament.game_name,
+        tournament.format.name(),
+        tournament.best_of,
+        tournament
+            .program_ids
+            .iter()
+            .map(|program_id| program_id.to_string())
+            .collect::<Vec<String>>()
+            .join(", "),
+        tournament.rounds.len(),
+        tournament.rounds_total,
+        games_played
+    );
+    for series in tournament.rounds.last().into_iter().flatten() {
+        let line = match (series.program2, series.winner) {
+            (None, _) => format!("`{}` bye", series.program1),
+            (Some(program2), None) if series.wins1 + series.wins2 + series.draws == 0 => {
+                format!("`{}` vs `{}`", series.program1, program2)
+            }
+            (Some(program2), _) => format!(
+                "`{}` {} - {} `{}`",
+                series.program1, series.wins1, series.wins2, program2
+            ),
+        };
+        content.push_str(&format!("\n> {}", line));
+    }
+    content
+}
+
+enum Failure {
+    Timeout(PlayerTurn),
+    Error(PlayerTurn),
+}
+
+// Play a game without any Discord message, a program that fails loses the game
+async fn play_headless(
+    handler: &Handler,
+    entry: &dyn GameEntry,
+    game: Box<dyn AnyGame>,
+    config: String,
+    program1_id: usize,
+    program2_id: usize,
+) -> Result<(GameOutcome, Option<Failure>)> {
+    let mut game_instance = GameInstance {
+        game,
+        game_name: entry.name(),
+        config,
+        player_turn: PlayerTurn::Player1,
+        file_player1: PathBuf::from(format!("tmp/{}.wasm", program1_id)),
+        file_player2: PathBuf::from(format!("tmp/{}.wasm", program2_id)),
+        automatic: true,
+        series: None,
+    };
+
+    let (outcome, failure) = loop {
+        let n = game_instance.player_turn;
+        match play_game_instance(&mut game_instance, None, &handler.sandbox).await? {
+            (GameOutcome::Ongoing, _, _) => (),
+            (outcome, _, None) => break (outcome, None),
+            (outcome, _, Some(forfeit)) if forfeit.error.is::<ProgramTimeout>() => {
+                break (outcome, Some(Failure::Timeout(n)))
+            }
+            (outcome, _, Some(_)) => break (outcome, Some(Failure::Error(n))),
+        }
+    };
+
+    if let Err(why) = handler
+        .ratings
+        .lock()
+        .await
+        .record(&game_instance, outcome)
+        .await
+    {
+        dbg!("Failed saving ratings: {}", why);
+    }
+    Ok((outcome, failure))
+}
+
+// "all" takes every program of ./tmp/
+async fn parse_program_ids(programs: &str) -> Result<Vec<usize>> {
+    let mut program_ids = Vec::new();
+    if programs.trim() == "all" {
+        let mut paths = tokio::fs::read_dir("./tmp/").await?;
+        while let Some(path) = paths.next_entry().await? {
+            let path = path.path();
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "wasm")
+            {
+                if let Some(program_id) = path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .and_then(|stem| stem.parse().ok())
+                {
+                    program_ids.push(program_id);
+                }
+            }
+        }
+    } else {
+        for program_id in programs.split([' ', ',']).filter(|s| !s.is_empty()) {
+            let program_id: usize = program_id
+                .parse()
+                .map_err(|_| Error::msg(format!("Invalid program id: {}", program_id)))?;
+            if !PathBuf::from(format!("tmp/{}.wasm", program_id)).try_exists()? {
+                return Err(Error::msg(format!(
+                    "Program file {} does not exists",
+                    program_id
+                )));
+            }
+            program_ids.push(program_id);
+        }
+    }
+    program_ids.sort();
+    program_ids.dedup();
+
+    if program_ids.len() < 2 {
+        return Err(Error::msg("A tournament needs at least 2 programs"));
+    }
+    Ok(program_ids)
+}
+
+fn standings_table(standings: &[&Standing]) -> String {
+    let rows = standings
+        .iter()
+        .enumerate()
+        .map(|(rank, standing)| {
+            format!(
+                "{:>2}. {:<8} {:>6} {:>4} {:>4} {:>4} {:>6} {:>8}",
+                rank + 1,
+                standing.program_id,
+                standing.points,
+                standing.wins,
+                standing.losses,
+                standing.draws,
+                standing.errors,
+                standing.timeouts
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    format!(
+        "```\n    {:<8} {:>6} {:>4} {:>4} {:>4} {:>6} {:>8}\n{}\n```",
+        "Program", "Points", "Wins", "Loss", "Draw", "Errors", "Timeouts", rows
+    )
+}
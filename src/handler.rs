@@ -1,11 +1,23 @@
 use crate::duel_buttons::play::play_button;
 use crate::duel_buttons::quit::quit_button;
+use crate::duel_buttons::replay::replay_button;
 use crate::duel_commands::add::{add_command, create_add_command};
+use crate::duel_commands::leaderboard::{create_leaderboard_command, leaderboard_command};
 use crate::duel_commands::list::{create_list_command, list_command};
+use crate::duel_commands::replay::{
+    create_replay_command, replay_command, REPLAY_NEXT_BUTTON_PREFIX, REPLAY_PREV_BUTTON_PREFIX,
+};
 use crate::duel_commands::start::{
-    create_start_command, start_command, PLAY_BUTTON_ID, QUIT_BUTTON_ID,
+    create_game_buttons, create_start_command, start_command, PLAY_BUTTON_ID, QUIT_BUTTON_ID,
 };
-use duel_game::{DiscordDuelGame, PlayerTurn};
+use crate::duel_commands::tournament::{create_tournament_command, tournament_command};
+use crate::ratings::Ratings;
+use crate::store::{load_games, save_game};
+use crate::tournament::load_unfinished_tournaments;
+use duel_engine::registry::{AnyGame, GameRegistry};
+use duel_engine::sandbox::Sandbox;
+use duel_game::PlayerTurn;
+use serde::{Deserialize, Serialize};
 use serenity::async_trait;
 use serenity::http::Http;
 use serenity::model::id::ChannelId;
@@ -15,22 +27,72 @@ use serenity::model::prelude::{GuildId, Interaction, MessageId, Ready};
 use serenity::prelude::{Context, EventHandler};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use tokio::sync::{Mutex, RwLock};
 
-pub struct GameInstance<GAME: DiscordDuelGame> {
-    pub game: GAME,
+pub struct GameInstance {
+    pub game: Box<dyn AnyGame>,
+    pub game_name: &'static str,
+    pub config: String,
     pub player_turn: PlayerTurn,
     pub file_player1: PathBuf,
     pub file_player2: PathBuf,
+    pub automatic: bool,
+    pub series: Option<GameSeries>,
+}
+
+// Best-of-N games between the same programs, the sides are swapped every game
+#[derive(Serialize, Deserialize, Clone)]
+pub struct GameSeries {
+    // Current game, starting at 1
+    pub game_number: usize,
+    pub games: usize,
+    // Saved game and displayed config of the next games
+    pub boards: Vec<(serde_json::Value, String)>,
+    // Seen from the programs of the Game Info
+    pub wins1: usize,
+    pub wins2: usize,
+    pub draws: usize,
+}
+
+impl GameInstance {
+    // Program of the Game Info (1 or 2) playing as the player `n`
+    pub fn program_number(&self, n: PlayerTurn) -> usize {
+        let swapped = self
+            .series
+            .as_ref()
+            .is_some_and(|series| series.game_number % 2 == 0);
+        match (n, swapped) {
+            (PlayerTurn::Player1, false) | (PlayerTurn::Player2, true) => 1,
+            _ => 2,
+        }
+    }
+
+    pub fn title(&self) -> String {
+        match &self.series {
+            Some(series) => format!("# Game {}/{}", series.game_number, series.games),
+            None => "# Game".to_string(),
+        }
+    }
 }
 
-pub struct Handler<GAME: DiscordDuelGame> {
+// The games are shared so that a move is played without holding the map lock
+pub type GameMap = HashMap<(ChannelId, MessageId), Arc<Mutex<GameInstance>>>;
+
+pub struct Handler {
     // pub number_game: AtomicUsize,
-    pub games: RwLock<HashMap<(ChannelId, MessageId), Mutex<GameInstance<GAME>>>>,
+    pub registry: GameRegistry,
+    pub games: RwLock<GameMap>,
+    pub ratings: Mutex<Ratings>,
+    pub sandbox: Sandbox,
+    // Set once the tournaments of a previous run are marked as interrupted, `ready` runs again
+    // on every new gateway session
+    pub tournaments_closed: AtomicBool,
 }
 
 #[async_trait]
-impl<GAME: DiscordDuelGame> EventHandler for Handler<GAME> {
+impl EventHandler for Handler {
     async fn ready(&self, ctx: Context, data_about_bot: Ready) {
         println!("Discord Bot \"{}\" is connected", data_about_bot.user.name);
 
@@ -42,7 +104,16 @@ impl<GAME: DiscordDuelGame> EventHandler for Handler<GAME> {
                 commands
                     .create_application_command(|command| create_add_command(command))
                     .create_application_command(|command| create_list_command(command))
-                    .create_application_command(|command| create_start_command::<GAME>(command))
+                    .create_application_command(|command| create_replay_command(command))
+                    .create_application_command(|command| {
+                        create_start_command(command, &self.registry)
+                    })
+                    .create_application_command(|command| {
+                        create_tournament_command(command, &self.registry)
+                    })
+                    .create_application_command(|command| {
+                        create_leaderboard_command(command, &self.registry)
+                    })
             })
             .await;
 
@@ -50,13 +121,86 @@ impl<GAME: DiscordDuelGame> EventHandler for Handler<GAME> {
                 dbg!("Failed creating commands: {}", why);
             }
         }
+
+        match load_games(&self.registry).await {
+            Ok(saved_games) => {
+                let mut games = self.games.write().await;
+                for (key, mut game_instance) in saved_games {
+                    if games.contains_key(&key) {
+                        continue;
+                    }
+                    if game_instance.automatic {
+                        // The automatic loop did not survive the restart, give back the Play button
+                        game_instance.automatic = false;
+                        let (channel_id, message_id) = key;
+                        if let Err(why) = channel_id
+                            .edit_message(&ctx.http, message_id, |message| {
+                                message.components(|c| create_game_buttons(c, false))
+                            })
+                            .await
+                        {
+                            dbg!("Failed restoring Play button: {}", why);
+                        }
+                        if let Err(why) = save_game(channel_id, message_id, &game_instance).await {
+                            dbg!("Failed saving game: {}", why);
+                        }
+                    }
+                    games.insert(key, Arc::new(Mutex::new(game_instance)));
+                }
+                println!("{} games running", games.len());
+            }
+            Err(why) => {
+                dbg!("Failed loading games: {}", why);
+            }
+        }
+
+        // Tournaments are not resumed, the saved ones only keep the rounds played before the restart
+        if self.tournaments_closed.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        match load_unfinished_tournaments().await {
+            Ok(tournaments) => {
+                for mut tournament in tournaments {
+                    let channel_id = ChannelId(tournament.channel_id);
+                    let message_id = MessageId(tournament.message_id);
+                    if let Ok(mut message) = channel_id.message(&ctx.http, message_id).await {
+                        let content = message.content.clone();
+                        if let Err(why) = message
+                            .edit(&ctx.http, |message| {
+                                message.content(format!("{}\n> TOURNAMENT INTERRUPTED", content))
+                            })
+                            .await
+                        {
+                            dbg!("Failed editing tournament: {}", why);
+                        }
+                    }
+                    tournament.finished = true;
+                    if let Err(why) = tournament.save().await {
+                        dbg!("Failed saving tournament: {}", why);
+                    }
+                }
+            }
+            Err(why) => {
+                dbg!("Failed loading tournaments: {}", why);
+            }
+        }
     }
 
     async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
         match interaction {
             Interaction::ApplicationCommand(command) => match command.data.name.as_str() {
                 "start" => {
-                    if let Err(error) = start_command::<GAME>(self, &ctx, &command).await {
+                    if let Err(error) = start_command(self, &ctx, &command).await {
+                        send_error_application_command(&ctx.http, command, error).await;
+                    }
+                }
+                "tournament" => {
+                    if let Err(error) = tournament_command(self, &ctx, &command).await {
+                        send_error_application_command(&ctx.http, command, error).await;
+                    }
+                }
+                "leaderboard" => {
+                    if let Err(error) = leaderboard_command(self, &ctx, &command).await {
                         send_error_application_command(&ctx.http, command, error).await;
                     }
                 }
@@ -70,6 +214,11 @@ impl<GAME: DiscordDuelGame> EventHandler for Handler<GAME> {
                         send_error_application_command(&ctx.http, command, error).await;
                     }
                 }
+                "replay" => {
+                    if let Err(error) = replay_command(&ctx, &command).await {
+                        send_error_application_command(&ctx.http, command, error).await;
+                    }
+                }
                 _ => unreachable!(),
             },
             Interaction::MessageComponent(command) => match command.data.custom_id.as_str() {
@@ -83,6 +232,14 @@ impl<GAME: DiscordDuelGame> EventHandler for Handler<GAME> {
                         send_error_message_component(&ctx.http, command, error).await;
                     }
                 }
+                custom_id
+                    if custom_id.starts_with(REPLAY_PREV_BUTTON_PREFIX)
+                        || custom_id.starts_with(REPLAY_NEXT_BUTTON_PREFIX) =>
+                {
+                    if let Err(error) = replay_button(&ctx, &command).await {
+                        send_error_message_component(&ctx.http, command, error).await;
+                    }
+                }
                 _ => unreachable!(),
             },
             _ => unreachable!(),
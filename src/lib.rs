@@ -0,0 +1,14 @@
+use duel_engine::registry::GameRegistry;
+use game_amazons::AmazonsGame;
+use game_connect4::Connect4Game;
+use game_mnk::MnkGame;
+use game_reversi::ReversiGame;
+
+// Every game of the bot and of the command line runner
+pub fn game_registry() -> GameRegistry {
+    GameRegistry::default()
+        .register::<AmazonsGame>("amazons", "Play a Game of the Amazons")
+        .register::<Connect4Game>("connect4", "Play a Connect Four")
+        .register::<MnkGame>("mnk", "Play a m,n,k-game (like Tic-tac-toe)")
+        .register::<ReversiGame>("reversi", "Play a Reversi (Othello)")
+}
@@ -1,15 +1,24 @@
 mod duel_buttons;
 mod duel_commands;
 mod handler;
+mod options;
+mod ratings;
+mod replay;
+mod store;
+mod tournament;
 
+use duel_engine::sandbox::Sandbox;
 use handler::Handler;
 use konst::primitive::parse_u64;
 use konst::unwrap_ctx;
+use ratings::Ratings;
+use rust_discord_duel::game_registry;
 use serenity::prelude::GatewayIntents;
 use serenity::Client;
 use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
 use tokio::fs::create_dir_all;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 
 const TOKEN: &str = include_str!("../res/token.txt");
 const APPLICATION_ID: u64 = unwrap_ctx!(parse_u64(include_str!("../res/application_id.txt")));
@@ -18,13 +27,20 @@ const APPLICATION_ID: u64 = unwrap_ctx!(parse_u64(include_str!("../res/applicati
 async fn main() {
     // let _ = remove_dir_all("./tmp/").await;
     create_dir_all("./tmp/").await.unwrap();
+    create_dir_all(store::GAMES_DIR).await.unwrap();
+    create_dir_all(replay::REPLAYS_DIR).await.unwrap();
+    create_dir_all(tournament::TOURNAMENTS_DIR).await.unwrap();
 
     let intents = GatewayIntents::empty();
 
     let mut client = Client::builder(TOKEN, intents)
-        .event_handler(Handler::<game_amazons::AmazonsGame> {
+        .event_handler(Handler {
             // number_game: AtomicUsize::new(0),
+            registry: game_registry(),
             games: RwLock::new(HashMap::with_capacity(10)),
+            ratings: Mutex::new(Ratings::load().await.expect("Error loading the ratings")),
+            sandbox: Sandbox::from_env().expect("Error creating the sandbox"),
+            tournaments_closed: AtomicBool::new(false),
         })
         .application_id(APPLICATION_ID)
         .await
@@ -0,0 +1,48 @@
+use duel_game::{ConfigOption, GameOption, OptionKind, OptionValue};
+use serenity::builder::CreateApplicationCommandOption;
+use serenity::model::application::command::CommandOptionType;
+use serenity::model::prelude::application_command::{CommandDataOption, CommandDataOptionValue};
+
+// Add the config options of a game to its subcommand
+pub fn create_config_options(
+    subcommand: &mut CreateApplicationCommandOption,
+    config_options: Vec<ConfigOption>,
+) -> &mut CreateApplicationCommandOption {
+    for config_option in config_options {
+        subcommand.create_sub_option(|option| {
+            option
+                .name(config_option.name)
+                .description(config_option.description)
+                .required(false)
+                .kind(match config_option.kind {
+                    OptionKind::String => CommandOptionType::String,
+                    OptionKind::Integer => CommandOptionType::Integer,
+                    OptionKind::Boolean => CommandOptionType::Boolean,
+                });
+            for choice in config_option.choices {
+                option.add_string_choice(choice, choice);
+            }
+            option
+        });
+    }
+    subcommand
+}
+
+// Options of a Discord subcommand as game options, other kinds of value are skipped
+pub fn game_options(options: &[CommandDataOption]) -> Vec<GameOption> {
+    options
+        .iter()
+        .filter_map(|option| {
+            let value = match option.resolved.as_ref()? {
+                CommandDataOptionValue::String(s) => OptionValue::String(s.clone()),
+                CommandDataOptionValue::Integer(i) => OptionValue::Integer(*i),
+                CommandDataOptionValue::Boolean(b) => OptionValue::Boolean(*b),
+                _ => return None,
+            };
+            Some(GameOption {
+                name: option.name.clone(),
+                value,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,145 @@
+#[cfg(test)]
+mod tests;
+
+use crate::handler::GameInstance;
+use crate::replay::program_name;
+use anyhow::Result;
+use duel_game::{GameOutcome, PlayerTurn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub const RATINGS_FILE: &str = "./ratings.json";
+
+const INITIAL_ELO: f64 = 1500.0;
+const K_FACTOR: f64 = 32.0;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Rating {
+    pub elo: f64,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+}
+
+impl Default for Rating {
+    fn default() -> Self {
+        Self {
+            elo: INITIAL_ELO,
+            wins: 0,
+            draws: 0,
+            losses: 0,
+        }
+    }
+}
+
+impl Rating {
+    pub fn games(&self) -> usize {
+        self.wins + self.draws + self.losses
+    }
+
+    // Draws count as half a win
+    pub fn win_rate(&self) -> f64 {
+        if self.games() == 0 {
+            return 0.0;
+        }
+        (self.wins as f64 + self.draws as f64 / 2.0) / self.games() as f64
+    }
+}
+
+// Elo ratings of the programs (by file name) for each game type
+#[derive(Serialize, Deserialize, Default)]
+pub struct Ratings(BTreeMap<String, BTreeMap<String, Rating>>);
+
+impl Ratings {
+    pub async fn load() -> Result<Self> {
+        match tokio::fs::read(RATINGS_FILE).await {
+            Ok(content) => Ok(serde_json::from_slice(&content)?),
+            Err(why) if why.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(why) => Err(why.into()),
+        }
+    }
+
+    async fn save(&self) -> Result<()> {
+        // Write then rename so a crash never leaves half written ratings
+        let tmp_path = format!("{}.tmp", RATINGS_FILE);
+        tokio::fs::write(&tmp_path, serde_json::to_vec(self)?).await?;
+        tokio::fs::rename(tmp_path, RATINGS_FILE).await?;
+        Ok(())
+    }
+
+    // Update and save the ratings of both programs of a finished game
+    pub async fn record(
+        &mut self,
+        game_instance: &GameInstance,
+        outcome: GameOutcome,
+    ) -> Result<()> {
+        let program1 = program_name(&game_instance.file_player1);
+        let program2 = program_name(&game_instance.file_player2);
+        if self.update(game_instance.game_name, program1, program2, outcome) {
+            self.save().await?;
+        }
+        Ok(())
+    }
+
+    // Elo update of a finished game, false when nothing is rated
+    fn update(
+        &mut self,
+        game_name: &str,
+        program1: String,
+        program2: String,
+        outcome: GameOutcome,
+    ) -> bool {
+        let score1 = match outcome {
+            GameOutcome::Ongoing => return false,
+            GameOutcome::Win(PlayerTurn::Player1) => 1.0,
+            GameOutcome::Win(PlayerTurn::Player2) => 0.0,
+            GameOutcome::Draw => 0.5,
+        };
+        if program1 == program2 {
+            return false;
+        }
+
+        let ratings = self.0.entry(game_name.to_string()).or_default();
+        let mut rating1 = ratings.get(&program1).cloned().unwrap_or_default();
+        let mut rating2 = ratings.get(&program2).cloned().unwrap_or_default();
+
+        let expected1 = 1.0 / (1.0 + 10f64.powf((rating2.elo - rating1.elo) / 400.0));
+        rating1.elo += K_FACTOR * (score1 - expected1);
+        rating2.elo -= K_FACTOR * (score1 - expected1);
+        match outcome {
+            GameOutcome::Win(PlayerTurn::Player1) => {
+                rating1.wins += 1;
+                rating2.losses += 1;
+            }
+            GameOutcome::Win(PlayerTurn::Player2) => {
+                rating1.losses += 1;
+                rating2.wins += 1;
+            }
+            _ => {
+                rating1.draws += 1;
+                rating2.draws += 1;
+            }
+        }
+        ratings.insert(program1, rating1);
+        ratings.insert(program2, rating2);
+        true
+    }
+
+    pub fn elo(&self, game_name: &str, program: &str) -> f64 {
+        self.0
+            .get(game_name)
+            .and_then(|ratings| ratings.get(program))
+            .map_or(INITIAL_ELO, |rating| rating.elo)
+    }
+
+    // Programs of a game type, best rating first
+    pub fn leaderboard(&self, game_name: &str) -> Vec<(&String, &Rating)> {
+        let mut leaderboard: Vec<(&String, &Rating)> = self
+            .0
+            .get(game_name)
+            .map(|ratings| ratings.iter().collect())
+            .unwrap_or_default();
+        leaderboard.sort_by(|(_, a), (_, b)| b.elo.total_cmp(&a.elo));
+        leaderboard
+    }
+}
@@ -0,0 +1,79 @@
+use crate::ratings::Ratings;
+use duel_game::{GameOutcome, PlayerTurn};
+
+fn update(ratings: &mut Ratings, program1: &str, program2: &str, outcome: GameOutcome) -> bool {
+    ratings.update("mnk", program1.to_string(), program2.to_string(), outcome)
+}
+
+#[test]
+fn win_between_new_programs() {
+    let mut ratings = Ratings::default();
+    assert!(update(
+        &mut ratings,
+        "1",
+        "2",
+        GameOutcome::Win(PlayerTurn::Player1)
+    ));
+    assert_eq!(ratings.elo("mnk", "1"), 1516.0);
+    assert_eq!(ratings.elo("mnk", "2"), 1484.0);
+    // Other games keep their own ratings
+    assert_eq!(ratings.elo("connect4", "1"), 1500.0);
+
+    let leaderboard = ratings.leaderboard("mnk");
+    assert_eq!(leaderboard[0].0, "1");
+    assert_eq!((leaderboard[0].1.wins, leaderboard[0].1.losses), (1, 0));
+    assert_eq!((leaderboard[1].1.wins, leaderboard[1].1.losses), (0, 1));
+}
+
+#[test]
+fn draw_moves_towards_the_weaker_program() {
+    let mut ratings = Ratings::default();
+    update(&mut ratings, "1", "2", GameOutcome::Draw);
+    assert_eq!(ratings.elo("mnk", "1"), 1500.0);
+    assert_eq!(ratings.leaderboard("mnk")[0].1.draws, 1);
+
+    update(
+        &mut ratings,
+        "1",
+        "2",
+        GameOutcome::Win(PlayerTurn::Player1),
+    );
+    update(&mut ratings, "1", "2", GameOutcome::Draw);
+    let elo1 = ratings.elo("mnk", "1");
+    let elo2 = ratings.elo("mnk", "2");
+    assert!(elo1 < 1516.0 && elo2 > 1484.0);
+    assert!((elo1 + elo2 - 3000.0).abs() < 1e-9);
+}
+
+#[test]
+fn upset_gains_more_than_expected_win() {
+    let mut ratings = Ratings::default();
+    update(
+        &mut ratings,
+        "1",
+        "2",
+        GameOutcome::Win(PlayerTurn::Player1),
+    );
+    let before = ratings.elo("mnk", "2");
+    update(
+        &mut ratings,
+        "1",
+        "2",
+        GameOutcome::Win(PlayerTurn::Player2),
+    );
+    assert!(ratings.elo("mnk", "2") - before > 16.0);
+    assert_eq!(ratings.leaderboard("mnk")[0].1.win_rate(), 0.5);
+}
+
+#[test]
+fn unrated_games() {
+    let mut ratings = Ratings::default();
+    assert!(!update(&mut ratings, "1", "2", GameOutcome::Ongoing));
+    assert!(!update(
+        &mut ratings,
+        "1",
+        "1",
+        GameOutcome::Win(PlayerTurn::Player1)
+    ));
+    assert!(ratings.leaderboard("mnk").is_empty());
+}
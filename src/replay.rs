@@ -0,0 +1,113 @@
+use crate::handler::GameInstance;
+use anyhow::{Error, Result};
+use duel_engine::runner::ReplayTurn;
+use serde::{Deserialize, Serialize};
+use serenity::model::prelude::MessageId;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+
+pub const REPLAYS_DIR: &str = "./replays/";
+
+// Starts every game of a replay log, the turns of the game are appended after it (one JSON per
+// line). The games of a series each have their own header, with the sides of that game
+#[derive(Serialize, Deserialize)]
+pub struct ReplayHeader {
+    pub game_name: String,
+    pub config: String,
+    pub file_player1: PathBuf,
+    pub file_player2: PathBuf,
+    pub board: String,
+}
+
+impl ReplayHeader {
+    pub fn new(game_instance: &GameInstance) -> Self {
+        Self {
+            game_name: game_instance.game_name.to_string(),
+            config: game_instance.config.clone(),
+            file_player1: game_instance.file_player1.clone(),
+            file_player2: game_instance.file_player2.clone(),
+            board: game_instance.game.to_discord(),
+        }
+    }
+}
+
+// A line of a replay log
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum ReplayLine {
+    Header(ReplayHeader),
+    Turn(ReplayTurn),
+}
+
+pub struct ReplayGame {
+    pub header: ReplayHeader,
+    pub turns: Vec<ReplayTurn>,
+}
+
+// Turns of all the games of a match
+pub fn replay_steps(games: &[ReplayGame]) -> usize {
+    games.iter().map(|game| game.turns.len()).sum()
+}
+
+// Programs are named after their file: "tmp/12.wasm" is "12"
+pub fn program_name(file_path: &Path) -> String {
+    file_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+// The match id is the id of the game message
+fn replay_path(match_id: u64) -> PathBuf {
+    PathBuf::from(format!("{}{}.jsonl", REPLAYS_DIR, match_id))
+}
+
+pub async fn start_replay(match_id: MessageId, header: &ReplayHeader) -> Result<()> {
+    let mut line = serde_json::to_vec(header)?;
+    line.push(b'\n');
+    tokio::fs::write(replay_path(match_id.0), line).await?;
+    Ok(())
+}
+
+// Header of the next game of a series
+pub async fn record_game(match_id: MessageId, header: &ReplayHeader) -> Result<()> {
+    append_line(match_id, serde_json::to_vec(header)?).await
+}
+
+pub async fn record_turn(match_id: MessageId, turn: &ReplayTurn) -> Result<()> {
+    append_line(match_id, serde_json::to_vec(turn)?).await
+}
+
+async fn append_line(match_id: MessageId, mut line: Vec<u8>) -> Result<()> {
+    line.push(b'\n');
+    let mut file = tokio::fs::OpenOptions::new()
+        .append(true)
+        .open(replay_path(match_id.0))
+        .await?;
+    file.write_all(&line).await?;
+    Ok(())
+}
+
+pub async fn load_replay(match_id: u64) -> Result<Vec<ReplayGame>> {
+    let content = tokio::fs::read_to_string(replay_path(match_id))
+        .await
+        .map_err(|_| Error::msg(format!("No replay for match {}", match_id)))?;
+    let mut games: Vec<ReplayGame> = Vec::new();
+    for line in content.lines() {
+        match serde_json::from_str(line)? {
+            ReplayLine::Header(header) => games.push(ReplayGame {
+                header,
+                turns: Vec::new(),
+            }),
+            ReplayLine::Turn(turn) => games
+                .last_mut()
+                .ok_or(Error::msg("Replay does not start with a header"))?
+                .turns
+                .push(turn),
+        }
+    }
+    if games.is_empty() {
+        return Err(Error::msg(format!("Empty replay for match {}", match_id)));
+    }
+    Ok(games)
+}
@@ -0,0 +1,111 @@
+use crate::handler::{GameInstance, GameSeries};
+use anyhow::{Error, Result};
+use duel_engine::registry::GameRegistry;
+use duel_game::PlayerTurn;
+use serde::{Deserialize, Serialize};
+use serenity::model::prelude::{ChannelId, MessageId};
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+pub const GAMES_DIR: &str = "./games/";
+
+// A running game as written in the store, one JSON file per game message
+#[derive(Serialize, Deserialize)]
+struct SavedGame {
+    channel_id: u64,
+    message_id: u64,
+    game_name: String,
+    config: String,
+    game: serde_json::Value,
+    player_turn: PlayerTurn,
+    file_player1: PathBuf,
+    file_player2: PathBuf,
+    automatic: bool,
+    #[serde(default)]
+    series: Option<GameSeries>,
+}
+
+fn game_path(channel_id: ChannelId, message_id: MessageId) -> PathBuf {
+    PathBuf::from(format!("{}{}_{}.json", GAMES_DIR, channel_id, message_id))
+}
+
+pub async fn save_game(
+    channel_id: ChannelId,
+    message_id: MessageId,
+    game_instance: &GameInstance,
+) -> Result<()> {
+    let saved_game = SavedGame {
+        channel_id: channel_id.0,
+        message_id: message_id.0,
+        game_name: game_instance.game_name.to_string(),
+        config: game_instance.config.clone(),
+        game: game_instance.game.save()?,
+        player_turn: game_instance.player_turn,
+        file_player1: game_instance.file_player1.clone(),
+        file_player2: game_instance.file_player2.clone(),
+        automatic: game_instance.automatic,
+        series: game_instance.series.clone(),
+    };
+
+    // Write then rename so a crash never leaves a half written game
+    let path = game_path(channel_id, message_id);
+    let tmp_path = path.with_extension("json.tmp");
+    tokio::fs::write(&tmp_path, serde_json::to_vec(&saved_game)?).await?;
+    tokio::fs::rename(tmp_path, path).await?;
+    Ok(())
+}
+
+pub async fn remove_game(channel_id: ChannelId, message_id: MessageId) -> Result<()> {
+    match tokio::fs::remove_file(game_path(channel_id, message_id)).await {
+        Err(why) if why.kind() != std::io::ErrorKind::NotFound => Err(why.into()),
+        _ => Ok(()),
+    }
+}
+
+pub async fn load_games(
+    registry: &GameRegistry,
+) -> Result<Vec<((ChannelId, MessageId), GameInstance)>> {
+    let mut games = Vec::new();
+    let mut paths = tokio::fs::read_dir(GAMES_DIR).await?;
+    while let Some(path) = paths.next_entry().await? {
+        let path = path.path();
+        if path.extension() != Some(OsStr::new("json")) {
+            continue;
+        }
+        match load_game(registry, &path).await {
+            Ok(game) => games.push(game),
+            Err(why) => println!("Cannot load game {}: {}", path.display(), why),
+        }
+    }
+    Ok(games)
+}
+
+async fn load_game(
+    registry: &GameRegistry,
+    path: &Path,
+) -> Result<((ChannelId, MessageId), GameInstance)> {
+    let saved_game: SavedGame = serde_json::from_slice(&tokio::fs::read(path).await?)?;
+    let entry = registry
+        .get(saved_game.game_name.as_str())
+        .ok_or(Error::msg(format!(
+            "Unknown game: {}",
+            saved_game.game_name
+        )))?;
+
+    Ok((
+        (
+            ChannelId(saved_game.channel_id),
+            MessageId(saved_game.message_id),
+        ),
+        GameInstance {
+            game: entry.load_game(saved_game.game)?,
+            game_name: entry.name(),
+            config: saved_game.config,
+            player_turn: saved_game.player_turn,
+            file_player1: saved_game.file_player1,
+            file_player2: saved_game.file_player2,
+            automatic: saved_game.automatic,
+            series: saved_game.series,
+        },
+    ))
+}
@@ -0,0 +1,329 @@
+#[cfg(test)]
+mod tests;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+pub const TOURNAMENTS_DIR: &str = "./tournaments/";
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Copy, Clone)]
+pub enum TournamentFormat {
+    // Every program plays every other program
+    RoundRobin,
+    // Programs with the same points play each other, for a fixed number of rounds
+    Swiss,
+    // Single elimination bracket, the loser of a series is out
+    Knockout,
+}
+
+impl TournamentFormat {
+    pub const ALL: [TournamentFormat; 3] = [
+        TournamentFormat::RoundRobin,
+        TournamentFormat::Swiss,
+        TournamentFormat::Knockout,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TournamentFormat::RoundRobin => "round_robin",
+            TournamentFormat::Swiss => "swiss",
+            TournamentFormat::Knockout => "knockout",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        TournamentFormat::ALL
+            .into_iter()
+            .find(|format| format.name() == name)
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct Standing {
+    pub program_id: usize,
+    // Series points: 1 for a won series (or a bye), 0.5 for a drawn one
+    pub points: f64,
+    pub wins: usize,
+    pub losses: usize,
+    pub draws: usize,
+    pub errors: usize,
+    pub timeouts: usize,
+}
+
+// Best-of-N games between two programs, program2 is None for a bye
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Series {
+    pub program1: usize,
+    pub program2: Option<usize>,
+    pub wins1: usize,
+    pub wins2: usize,
+    pub draws: usize,
+    pub winner: Option<usize>,
+}
+
+impl Series {
+    pub fn new(program1: usize, program2: Option<usize>) -> Self {
+        Self {
+            program1,
+            program2,
+            wins1: 0,
+            wins2: 0,
+            draws: 0,
+            winner: program2.is_none().then_some(program1),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Tournament {
+    pub channel_id: u64,
+    pub message_id: u64,
+    pub game_name: String,
+    pub format: TournamentFormat,
+    pub best_of: usize,
+    pub rounds_total: usize,
+    // In seed order, the first program is the best seed
+    pub program_ids: Vec<usize>,
+    pub standings: Vec<Standing>,
+    pub rounds: Vec<Vec<Series>>,
+    pub finished: bool,
+}
+
+impl Tournament {
+    pub fn new(
+        game_name: &str,
+        format: TournamentFormat,
+        best_of: usize,
+        swiss_rounds: Option<usize>,
+        program_ids: Vec<usize>,
+    ) -> Self {
+        // Enough rounds for a single winner
+        let log2_rounds = program_ids.len().next_power_of_two().trailing_zeros() as usize;
+        let rounds_total = match format {
+            TournamentFormat::RoundRobin => 1,
+            TournamentFormat::Swiss => swiss_rounds.unwrap_or(log2_rounds),
+            TournamentFormat::Knockout => log2_rounds,
+        };
+        Self {
+            channel_id: 0,
+            message_id: 0,
+            game_name: game_name.to_string(),
+            format,
+            best_of,
+            rounds_total,
+            standings: program_ids
+                .iter()
+                .map(|&program_id| Standing {
+                    program_id,
+                    ..Default::default()
+                })
+                .collect(),
+            program_ids,
+            rounds: Vec::new(),
+            finished: false,
+        }
+    }
+
+    pub fn standing_mut(&mut self, program_id: usize) -> &mut Standing {
+        self.standings
+            .iter_mut()
+            .find(|standing| standing.program_id == program_id)
+            .expect("Every program has a standing")
+    }
+
+    // Best points first, then most game wins, then fewest losses
+    pub fn ranking(&self) -> Vec<&Standing> {
+        let mut ranking: Vec<&Standing> = self.standings.iter().collect();
+        ranking.sort_by(|a, b| {
+            b.points
+                .total_cmp(&a.points)
+                .then(b.wins.cmp(&a.wins))
+                .then(a.losses.cmp(&b.losses))
+        });
+        ranking
+    }
+
+    // Winner of the final for a knockout
+    pub fn champion(&self) -> Option<usize> {
+        match self.format {
+            TournamentFormat::Knockout if self.finished => self.rounds.last()?.first()?.winner,
+            _ => None,
+        }
+    }
+
+    // Pairings of the next round, None when the tournament is over
+    pub fn next_round(&self) -> Option<Vec<Series>> {
+        if self.rounds.len() >= self.rounds_total {
+            return None;
+        }
+        match self.format {
+            TournamentFormat::RoundRobin => Some(self.round_robin_round()),
+            TournamentFormat::Swiss => Some(self.swiss_round()),
+            TournamentFormat::Knockout => self.knockout_round(),
+        }
+    }
+
+    fn round_robin_round(&self) -> Vec<Series> {
+        let mut round = Vec::new();
+        for (i, &program1) in self.program_ids.iter().enumerate() {
+            for &program2 in self.program_ids[i + 1..].iter() {
+                round.push(Series::new(program1, Some(program2)));
+            }
+        }
+        round
+    }
+
+    fn swiss_round(&self) -> Vec<Series> {
+        let mut unpaired: Vec<usize> = self
+            .ranking()
+            .iter()
+            .map(|standing| standing.program_id)
+            .collect();
+        let mut round = Vec::new();
+
+        // The lowest ranked program that never had a bye sits out
+        if unpaired.len() % 2 == 1 {
+            let bye_index = (0..unpaired.len())
+                .rev()
+                .find(|&index| !self.had_bye(unpaired[index]))
+                .unwrap_or(unpaired.len() - 1);
+            round.push(Series::new(unpaired.remove(bye_index), None));
+        }
+
+        // Pair from the top with the closest program not played yet
+        while !unpaired.is_empty() {
+            let program1 = unpaired.remove(0);
+            let index = unpaired
+                .iter()
+                .position(|&program2| !self.have_played(program1, program2))
+                .unwrap_or(0);
+            round.push(Series::new(program1, Some(unpaired.remove(index))));
+        }
+        round
+    }
+
+    fn knockout_round(&self) -> Option<Vec<Series>> {
+        let Some(last_round) = self.rounds.last() else {
+            // Seeds 1 and 2 can only meet in the final, missing seeds are byes
+            let seeds = bracket_seeds(self.program_ids.len().next_power_of_two());
+            return Some(
+                seeds
+                    .chunks(2)
+                    .map(|pair| {
+                        Series::new(
+                            self.program_ids[pair[0]],
+                            self.program_ids.get(pair[1]).copied(),
+                        )
+                    })
+                    .collect(),
+            );
+        };
+
+        let winners: Vec<usize> = last_round
+            .iter()
+            .filter_map(|series| series.winner)
+            .collect();
+        if winners.len() < 2 {
+            return None;
+        }
+        Some(
+            winners
+                .chunks(2)
+                .map(|pair| Series::new(pair[0], pair.get(1).copied()))
+                .collect(),
+        )
+    }
+
+    // Give the series points once all its games are played
+    pub fn finish_series(&mut self, series: &mut Series) {
+        let Some(program2) = series.program2 else {
+            self.standing_mut(series.program1).points += 1.0;
+            return;
+        };
+        series.winner = match series.wins1.cmp(&series.wins2) {
+            Ordering::Greater => Some(series.program1),
+            Ordering::Less => Some(program2),
+            Ordering::Equal => None,
+        };
+        match series.winner {
+            Some(winner) => self.standing_mut(winner).points += 1.0,
+            None => {
+                self.standing_mut(series.program1).points += 0.5;
+                self.standing_mut(program2).points += 0.5;
+            }
+        }
+
+        // A knockout needs a winner, the best seed goes through a drawn series
+        if self.format == TournamentFormat::Knockout && series.winner.is_none() {
+            series.winner = self
+                .program_ids
+                .iter()
+                .find(|&&program_id| program_id == series.program1 || program_id == program2)
+                .copied();
+        }
+    }
+
+    fn had_bye(&self, program_id: usize) -> bool {
+        self.rounds
+            .iter()
+            .flatten()
+            .any(|series| series.program1 == program_id && series.program2.is_none())
+    }
+
+    fn have_played(&self, program1: usize, program2: usize) -> bool {
+        self.rounds.iter().flatten().any(|series| {
+            (series.program1 == program1 && series.program2 == Some(program2))
+                || (series.program1 == program2 && series.program2 == Some(program1))
+        })
+    }
+
+    pub async fn save(&self) -> Result<()> {
+        // Write then rename so a crash never leaves a half written tournament
+        let path = tournament_path(self.message_id);
+        let tmp_path = path.with_extension("json.tmp");
+        tokio::fs::write(&tmp_path, serde_json::to_vec(self)?).await?;
+        tokio::fs::rename(tmp_path, path).await?;
+        Ok(())
+    }
+}
+
+// Bracket order of the seeds (0 is the best seed): [0, 3, 1, 2] for 4 programs
+fn bracket_seeds(size: usize) -> Vec<usize> {
+    let mut seeds = vec![0];
+    while seeds.len() < size {
+        let next_size = seeds.len() * 2;
+        seeds = seeds
+            .iter()
+            .flat_map(|&seed| [seed, next_size - 1 - seed])
+            .collect();
+    }
+    seeds
+}
+
+fn tournament_path(message_id: u64) -> PathBuf {
+    PathBuf::from(format!("{}{}.json", TOURNAMENTS_DIR, message_id))
+}
+
+pub async fn load_unfinished_tournaments() -> Result<Vec<Tournament>> {
+    let mut tournaments = Vec::new();
+    let mut paths = tokio::fs::read_dir(TOURNAMENTS_DIR).await?;
+    while let Some(path) = paths.next_entry().await? {
+        let path = path.path();
+        if path.extension() != Some(OsStr::new("json")) {
+            continue;
+        }
+        match load_tournament(&path).await {
+            Ok(tournament) if !tournament.finished => tournaments.push(tournament),
+            Ok(_) => (),
+            Err(why) => println!("Cannot load tournament {}: {}", path.display(), why),
+        }
+    }
+    Ok(tournaments)
+}
+
+async fn load_tournament(path: &Path) -> Result<Tournament> {
+    Ok(serde_json::from_slice(&tokio::fs::read(path).await?)?)
+}
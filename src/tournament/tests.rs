@@ -0,0 +1,149 @@
+use crate::tournament::{bracket_seeds, Series, Tournament, TournamentFormat};
+
+// Play the next round with the given series wins (wins1, wins2), byes are skipped
+fn play_round(tournament: &mut Tournament, wins: &[(usize, usize)]) -> Vec<Series> {
+    let mut round = tournament.next_round().expect("The tournament goes on");
+    let mut wins = wins.iter();
+    for series in round.iter_mut() {
+        if series.program2.is_some() {
+            (series.wins1, series.wins2) = *wins.next().expect("Every series has a result");
+        }
+        tournament.finish_series(series);
+    }
+    tournament.rounds.push(round.clone());
+    round
+}
+
+fn pairs(round: &[Series]) -> Vec<(usize, Option<usize>)> {
+    round
+        .iter()
+        .map(|series| (series.program1, series.program2))
+        .collect()
+}
+
+#[test]
+fn bracket_keeps_best_seeds_apart() {
+    assert_eq!(bracket_seeds(1), vec![0]);
+    assert_eq!(bracket_seeds(4), vec![0, 3, 1, 2]);
+    assert_eq!(bracket_seeds(8), vec![0, 7, 3, 4, 1, 6, 2, 5]);
+}
+
+#[test]
+fn round_robin_pairs_everyone_once() {
+    let mut tournament = Tournament::new(
+        "mnk",
+        TournamentFormat::RoundRobin,
+        2,
+        None,
+        vec![1, 2, 3, 4],
+    );
+    let round = play_round(
+        &mut tournament,
+        &[(2, 0), (1, 1), (0, 2), (2, 0), (0, 1), (1, 0)],
+    );
+    assert_eq!(
+        pairs(&round),
+        vec![
+            (1, Some(2)),
+            (1, Some(3)),
+            (1, Some(4)),
+            (2, Some(3)),
+            (2, Some(4)),
+            (3, Some(4))
+        ]
+    );
+    assert!(tournament.next_round().is_none());
+
+    // A drawn series is half a point each
+    let points = tournament
+        .ranking()
+        .iter()
+        .map(|standing| (standing.program_id, standing.points))
+        .collect::<Vec<(usize, f64)>>();
+    assert_eq!(points, vec![(4, 2.0), (1, 1.5), (3, 1.5), (2, 1.0)]);
+    assert_eq!(tournament.champion(), None);
+}
+
+#[test]
+fn swiss_gives_byes_and_avoids_rematches() {
+    let mut tournament = Tournament::new(
+        "mnk",
+        TournamentFormat::Swiss,
+        1,
+        Some(2),
+        vec![1, 2, 3, 4, 5],
+    );
+    let round = play_round(&mut tournament, &[(1, 0), (1, 0)]);
+    // The lowest ranked program sits out
+    assert_eq!(pairs(&round), vec![(5, None), (1, Some(2)), (3, Some(4))]);
+    assert_eq!(round[0].winner, Some(5));
+
+    // 1, 3 and 5 lead, 4 is the lowest ranked without a bye
+    let round = play_round(&mut tournament, &[(1, 0), (0, 1)]);
+    assert_eq!(pairs(&round), vec![(4, None), (1, Some(3)), (5, Some(2))]);
+    assert!(tournament.next_round().is_none());
+
+    let ranking = tournament.ranking();
+    assert_eq!(ranking[0].program_id, 1);
+    assert_eq!(ranking[0].points, 2.0);
+}
+
+#[test]
+fn swiss_pairs_a_rematch_only_when_needed() {
+    let mut tournament = Tournament::new("mnk", TournamentFormat::Swiss, 1, Some(2), vec![1, 2]);
+    play_round(&mut tournament, &[(1, 0)]);
+    let round = play_round(&mut tournament, &[(0, 1)]);
+    assert_eq!(pairs(&round), vec![(1, Some(2))]);
+}
+
+#[test]
+fn knockout_until_the_final() {
+    let mut tournament = Tournament::new(
+        "mnk",
+        TournamentFormat::Knockout,
+        2,
+        None,
+        vec![10, 20, 30, 40],
+    );
+    assert_eq!(tournament.rounds_total, 2);
+
+    // A drawn series sends the best seed through
+    let round = play_round(&mut tournament, &[(0, 2), (1, 1)]);
+    assert_eq!(pairs(&round), vec![(10, Some(40)), (20, Some(30))]);
+    assert_eq!(round[0].winner, Some(40));
+    assert_eq!(round[1].winner, Some(20));
+
+    let round = play_round(&mut tournament, &[(2, 1)]);
+    assert_eq!(pairs(&round), vec![(40, Some(20))]);
+    assert!(tournament.next_round().is_none());
+    assert_eq!(tournament.champion(), None);
+    tournament.finished = true;
+    assert_eq!(tournament.champion(), Some(40));
+}
+
+#[test]
+fn knockout_gives_byes_to_the_best_seeds() {
+    let mut tournament = Tournament::new("mnk", TournamentFormat::Knockout, 1, None, vec![1, 2, 3]);
+    let round = play_round(&mut tournament, &[(0, 1)]);
+    assert_eq!(pairs(&round), vec![(1, None), (2, Some(3))]);
+    let round = play_round(&mut tournament, &[(1, 0)]);
+    assert_eq!(pairs(&round), vec![(1, Some(3))]);
+}
+
+#[test]
+fn ranking_breaks_ties_with_wins_then_losses() {
+    let mut tournament =
+        Tournament::new("mnk", TournamentFormat::RoundRobin, 2, None, vec![1, 2, 3]);
+    for (program_id, wins, losses) in [(1, 1, 2), (2, 2, 1), (3, 1, 1)] {
+        let standing = tournament.standing_mut(program_id);
+        standing.points = 1.0;
+        standing.wins = wins;
+        standing.losses = losses;
+    }
+    let ranking = tournament
+        .ranking()
+        .iter()
+        .map(|standing| standing.program_id)
+        .collect::<Vec<usize>>();
+    assert_eq!(ranking, vec![2, 3, 1]);
+}